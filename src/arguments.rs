@@ -27,17 +27,106 @@ pub enum Commands {
     Deactivate(crate::commands::deactivate::Command),
     #[clap(name = "hook", about = "Command to be executed on directory change")]
     Hook(crate::commands::chdir_hook::Command),
+    #[clap(name = "alias", about = "Manage short aliases of virtual environments")]
+    Alias(crate::commands::alias::AliasCommand),
+    #[clap(name = "info", about = "Show details of a virtual environment")]
+    Info(crate::commands::info::InfoCommand),
     #[clap(name = "list", about = "List existing virtual environments")]
     List(crate::commands::list::ListCommand),
     #[clap(name = "create", about = "Create a new virtual environment")]
     Create(crate::commands::create::CreateCommand),
+    #[clap(
+        name = "presets",
+        about = "List creation presets defined in the configuration file"
+    )]
+    Presets(crate::commands::presets::PresetsCommand),
+    #[clap(
+        name = "prompt",
+        about = "Print a prompt segment describing the active virtual environment"
+    )]
+    Prompt(crate::commands::prompt::PromptCommand),
+    #[clap(
+        name = "clone",
+        about = "Copy a virtual environment into the rsvenv store"
+    )]
+    Clone(crate::commands::clone::CloneCommand),
+    #[clap(
+        name = "exec",
+        about = "Run a command inside a virtual environment without activating it"
+    )]
+    Exec(crate::commands::exec::ExecCommand),
+    #[clap(
+        name = "export",
+        about = "Pack a virtual environment into a portable archive"
+    )]
+    Export(crate::commands::archive::ExportCommand),
+    #[clap(
+        name = "export-spec",
+        about = "Print installed packages of a virtual environment as requirements, pyproject or conda YAML"
+    )]
+    ExportSpec(crate::commands::export_spec::ExportSpecCommand),
+    #[clap(
+        name = "import",
+        about = "Unpack a virtual environment archive into the rsvenv store"
+    )]
+    Import(crate::commands::archive::ImportCommand),
+    #[clap(
+        name = "current",
+        about = "Show the virtual environment of the current directory and what decided it"
+    )]
+    Current(crate::commands::current::CurrentCommand),
     #[clap(name = "delete", about = "Delete a virtual environment")]
     Delete(crate::commands::delete::DeleteCommand),
+    #[clap(name = "du", about = "Show disk usage of virtual environments")]
+    Du(crate::commands::du::DuCommand),
+    #[clap(
+        name = "gc",
+        about = "Find and remove or repair broken virtual environments"
+    )]
+    Gc(crate::commands::gc::GcCommand),
+    #[clap(name = "rename", about = "Rename a virtual environment")]
+    Rename(crate::commands::rename::RenameCommand),
+    #[clap(
+        name = "repair",
+        about = "Point a virtual environment to another interpreter of the same minor version"
+    )]
+    Repair(crate::commands::repair::RepairCommand),
+    #[clap(
+        name = "restore",
+        about = "Restore a deleted virtual environment from the trash"
+    )]
+    Restore(crate::commands::restore::RestoreCommand),
+    #[clap(
+        name = "shell",
+        about = "Start a subshell with a virtual environment activated"
+    )]
+    Shell(crate::commands::shell::ShellCommand),
+    #[clap(name = "snapshot", about = "Save a snapshot of a virtual environment")]
+    Snapshot(crate::commands::snapshot::SnapshotCommand),
+    #[clap(
+        name = "rollback",
+        about = "Restore a virtual environment from a snapshot"
+    )]
+    Rollback(crate::commands::snapshot::RollbackCommand),
+    #[clap(name = "snapshots", about = "List snapshots of a virtual environment")]
+    Snapshots(crate::commands::snapshot::SnapshotsCommand),
+    #[clap(name = "trash", about = "Manage deleted virtual environments")]
+    Trash(crate::commands::trash::TrashCommand),
+    #[clap(
+        name = "upgrade",
+        about = "Recreate a virtual environment with another python version keeping its packages"
+    )]
+    Upgrade(crate::commands::upgrade::UpgradeCommand),
     #[clap(
         name = "use",
         about = "Use the virtual environment in the current directory"
     )]
     Use(crate::commands::use_command::UseCommand),
+    #[clap(
+        name = "which",
+        about = "Show the executable of a command inside virtual environments"
+    )]
+    Which(crate::commands::which::WhichCommand),
 }
 
 impl Commands {
@@ -48,11 +137,33 @@ impl Commands {
             Commands::Activate(command) => command.execute(),
             Commands::Deactivate(command) => command.execute(),
             Commands::Hook(command) => command.execute(),
+            Commands::Alias(command) => command.execute(),
+            Commands::Info(command) => command.execute(),
             Commands::List(command) => command.execute(),
             Commands::Use(command) => command.execute(),
             Commands::Create(command) => command.execute(),
+            Commands::Du(command) => command.execute(),
+            Commands::Gc(command) => command.execute(),
+            Commands::Presets(command) => command.execute(),
+            Commands::Prompt(command) => command.execute(),
+            Commands::Clone(command) => command.execute(),
+            Commands::Exec(command) => command.execute(),
+            Commands::Export(command) => command.execute(),
+            Commands::ExportSpec(command) => command.execute(),
+            Commands::Import(command) => command.execute(),
+            Commands::Current(command) => command.execute(),
             Commands::Delete(command) => command.execute(),
             Commands::Install(command) => command.execute(),
+            Commands::Rename(command) => command.execute(),
+            Commands::Repair(command) => command.execute(),
+            Commands::Restore(command) => command.execute(),
+            Commands::Shell(command) => command.execute(),
+            Commands::Snapshot(command) => command.execute(),
+            Commands::Rollback(command) => command.execute(),
+            Commands::Snapshots(command) => command.execute(),
+            Commands::Trash(command) => command.execute(),
+            Commands::Upgrade(command) => command.execute(),
+            Commands::Which(command) => command.execute(),
         }
     }
 }
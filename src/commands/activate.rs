@@ -11,7 +11,7 @@ pub struct Command {
 }
 
 fn try_activate(v: VirtualEnvironment, venv: &String) -> Result<()> {
-    if v.list().contains(venv) {
+    if v.list().contains(&v.kind.resolve(venv)) {
         if let Err(e) = v.activate(Some(venv)) {
             debug!("Unable to activate venv: {e:?}");
             bail!(e);
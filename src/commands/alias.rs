@@ -0,0 +1,71 @@
+use std::io;
+use std::io::Write;
+
+use crate::virtualenv::{
+    aliases::Aliases, projects::Projects, rsenv::Rsenv, traits::VirtualEnvCompatible,
+};
+use anyhow::{bail, Result};
+use clap::{Parser, Subcommand};
+use simplelog::info;
+
+#[derive(Debug, Parser)]
+pub struct AliasCommand {
+    #[command(subcommand)]
+    command: AliasSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum AliasSubcommand {
+    #[clap(name = "set", about = "Point an alias to a virtual environment")]
+    Set {
+        #[clap(help = "Alias name")]
+        alias: String,
+        #[clap(help = "Virtual environment name")]
+        venv: String,
+    },
+    #[clap(name = "list", about = "List aliases")]
+    List,
+    #[clap(name = "remove", about = "Remove an alias")]
+    Remove {
+        #[clap(help = "Alias name")]
+        alias: String,
+    },
+}
+
+impl AliasCommand {
+    pub fn execute(&self) -> Result<()> {
+        match &self.command {
+            AliasSubcommand::Set { alias, venv } => {
+                let venvs = Rsenv.list();
+                if venvs.contains(alias) {
+                    bail!("Alias {alias} conflicts with an existing venv");
+                }
+                let venv = Aliases::resolve(venv);
+                if !venvs.contains(&venv) {
+                    bail!("Venv {venv} doesn't exist");
+                }
+                Aliases::set(alias, &venv)?;
+                let projects = Projects::using(alias);
+                if !projects.is_empty() {
+                    info!("{} projects now use {venv}", projects.len());
+                }
+                Ok(())
+            }
+            AliasSubcommand::List => {
+                for (alias, venv) in Aliases::load() {
+                    io::stdout()
+                        .write_all(format!("{alias}\t{venv}\n").as_bytes())
+                        .unwrap_or(());
+                }
+                Ok(())
+            }
+            AliasSubcommand::Remove { alias } => {
+                Aliases::remove(alias)?;
+                for marker in Projects::using(alias) {
+                    info!("{} still uses {alias}", marker.display());
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Result;
+use clap::Parser;
+
+use crate::virtualenv::{archive, find_backend, rsenv::Rsenv, traits::VirtualEnvCompatible};
+
+#[derive(Debug, Parser)]
+pub struct ExportCommand {
+    #[clap(help = "Virtual environment name")]
+    venv: String,
+    #[clap(
+        long,
+        short,
+        help = "Archive path, compressed with zstd when it ends with .zst"
+    )]
+    output: PathBuf,
+}
+
+impl ExportCommand {
+    pub fn execute(&self) -> Result<()> {
+        let backend = find_backend(&self.venv)?;
+        let path = backend.path(Some(&self.venv))?;
+        let path = fs::canonicalize(&path).unwrap_or(path);
+        let name = if backend.backend() == Rsenv.backend() {
+            self.venv.clone()
+        } else {
+            // The archive is imported into the store, where names start with the python version
+            let basename = self.venv.rsplit('/').next().unwrap_or(&self.venv);
+            Rsenv::versioned_name(&path, basename)?
+        };
+        archive::export(&name, &path, &self.output)
+    }
+}
+
+#[derive(Debug, Parser)]
+pub struct ImportCommand {
+    #[clap(help = "Archive created by rsvenv export")]
+    archive: PathBuf,
+    #[clap(long, help = "Virtual environment name. Defaults to the exported name")]
+    name: Option<String>,
+}
+
+impl ImportCommand {
+    pub fn execute(&self) -> Result<()> {
+        archive::import(&self.archive, self.name.as_ref())?;
+        Ok(())
+    }
+}
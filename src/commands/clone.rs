@@ -0,0 +1,29 @@
+use std::fs;
+
+use anyhow::Result;
+use clap::Parser;
+
+use crate::virtualenv::{find_backend, rsenv::Rsenv, traits::VirtualEnvCompatible};
+
+#[derive(Debug, Parser)]
+pub struct CloneCommand {
+    #[clap(help = "Virtual environment to copy")]
+    src: String,
+    #[clap(help = "Name of the copy. Without python version the version of the source is used")]
+    dst: String,
+}
+
+impl CloneCommand {
+    pub fn execute(&self) -> Result<()> {
+        let backend = find_backend(&self.src)?;
+        let path = backend.path(Some(&self.src))?;
+        let path = fs::canonicalize(&path).unwrap_or(path);
+        let name = if backend.backend() == Rsenv.backend() || self.dst.contains('/') {
+            Rsenv::target_name(&self.src, &self.dst)
+        } else {
+            // Names of other backends don't start with the python version
+            Rsenv::versioned_name(&path, &self.dst)?
+        };
+        Rsenv.clone_from(&path, &name)
+    }
+}
@@ -1,18 +1,76 @@
-use anyhow::Result;
+use anyhow::{anyhow, bail, Context, Result};
 use clap::Parser;
+use simplelog::error;
 
-use crate::virtualenv::rsenv::Rsenv;
+use crate::configuration::{Preset, SETTINGS};
+use crate::virtualenv::{rsenv::Rsenv, traits::VirtualEnvCompatible, VirtualEnvironment};
 
 #[derive(Debug, Parser)]
 pub struct CreateCommand {
-    #[clap(help = "Path to python executable")]
-    python: String,
+    #[clap(help = "Path to python executable. May be omitted when --preset is used")]
+    python: Option<String>,
     #[clap(help = "Virtualenv name")]
-    name: String,
+    name: Option<String>,
+    #[clap(long, help = "Name of the preset defined in the configuration file")]
+    preset: Option<String>,
+    #[clap(
+        long = "use",
+        action,
+        help = "Use the new virtual environment in the current directory and activate it"
+    )]
+    use_venv: bool,
+}
+
+fn get_preset(name: &str) -> Result<Preset> {
+    SETTINGS
+        .read()
+        .map_err(|e| anyhow!("Unable to read presets from SETTINGS: {e:?}"))?
+        .presets
+        .get(name)
+        .cloned()
+        .ok_or_else(|| anyhow!("Preset {name} is not defined"))
 }
 
 impl CreateCommand {
+    fn resolve(&self) -> Result<(String, String, Preset)> {
+        match (&self.preset, &self.python, &self.name) {
+            (Some(preset), Some(name), None) => {
+                let preset = get_preset(preset)?;
+                Ok((preset.python.clone(), name.clone(), preset))
+            }
+            (Some(preset), Some(python), Some(name)) => {
+                Ok((python.clone(), name.clone(), get_preset(preset)?))
+            }
+            (None, Some(python), Some(name)) => Ok((
+                python.clone(),
+                name.clone(),
+                Preset {
+                    python: python.clone(),
+                    venv_options: vec![],
+                    packages: vec![],
+                },
+            )),
+            _ => bail!("Both python executable and virtualenv name are required without --preset"),
+        }
+    }
+
     pub fn execute(&self) -> Result<()> {
-        Rsenv.create(&self.name, &self.python)
+        let (python, name, preset) = self.resolve()?;
+        let name = Rsenv.create(&name, &python, &preset.venv_options)?;
+        if let Err(e) = Rsenv.install(&name, &preset.packages) {
+            error!("Installing packages failed, removing {name}");
+            Rsenv
+                .delete(&name, true)
+                .with_context(|| format!("Unable to clean up {name}"))?;
+            bail!(e);
+        }
+        if self.use_venv {
+            Rsenv.save(&name)?;
+            if std::env::var("VIRTUAL_ENV").is_ok() {
+                VirtualEnvironment::deactivate(false)?;
+            }
+            VirtualEnvironment::new(&Rsenv)?.activate(Some(&name))?;
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,70 @@
+use std::{fmt, fs, io, io::Write, path::PathBuf};
+
+use anyhow::Result;
+use clap::Parser;
+
+use crate::virtualenv::{detect_backend, find_by_path, projects::Projects, utils::get_current_dir};
+
+#[derive(Debug, Parser)]
+pub struct CurrentCommand {}
+
+// What decides the venv of the current directory
+enum Source {
+    Marker(PathBuf),
+    Local(PathBuf),
+    Manual,
+    None,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::Marker(path) => write!(f, "set by {}", path.display()),
+            Source::Local(dir) => write!(f, "local venv in {}", dir.display()),
+            Source::Manual => write!(f, "activated manually"),
+            Source::None => write!(f, "no virtual environment"),
+        }
+    }
+}
+
+// Name of the venv at the path if it is known to a backend, the path otherwise
+fn display_name(path: &str) -> String {
+    let path = PathBuf::from(path);
+    let path = fs::canonicalize(&path).unwrap_or(path);
+    find_by_path(&path)
+        .map(|(_, name)| name)
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+impl CurrentCommand {
+    pub fn execute(&self) -> Result<()> {
+        let active = std::env::var("VIRTUAL_ENV").ok();
+        let (venv, source) = match detect_backend() {
+            Some(backend) => {
+                let name = backend.venv_name()?;
+                let resolved = backend.resolve(&name);
+                let venv = if resolved == name {
+                    name
+                } else {
+                    format!("{name} -> {resolved}")
+                };
+                let cwd = get_current_dir()?;
+                let source = match Projects::marker(&cwd) {
+                    Some((marker, _)) if backend.backend() != "local" => Source::Marker(marker),
+                    _ => Source::Local(cwd),
+                };
+                (venv, source)
+            }
+            None => match &active {
+                Some(path) => (display_name(path), Source::Manual),
+                None => ("system".to_string(), Source::None),
+            },
+        };
+        let output = format!(
+            "{venv} ({source})\nVIRTUAL_ENV: {}\n",
+            active.as_deref().unwrap_or("-")
+        );
+        io::stdout().write_all(output.as_bytes()).unwrap_or(());
+        Ok(())
+    }
+}
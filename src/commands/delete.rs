@@ -1,15 +1,223 @@
-use crate::virtualenv::rsenv::Rsenv;
-use anyhow::Result;
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use crate::virtualenv::{
+    aliases::Aliases,
+    find_backend,
+    projects::Projects,
+    pyvenv_cfg::{version_matches, PyvenvCfg},
+    rsenv::Rsenv,
+    traits::VirtualEnvCompatible,
+    utils::{confirm, dir_size, format_size, is_active, last_used},
+    VirtualEnvironment,
+};
+use anyhow::{bail, Context, Result};
 use clap::Parser;
+use glob::{MatchOptions, Pattern};
+use itertools::Itertools;
+use simplelog::{info, warn};
 
 #[derive(Debug, Parser)]
 pub struct DeleteCommand {
-    #[clap(help = "Virtual environment name")]
-    venv: String,
+    #[clap(help = "Virtual environment names or glob patterns")]
+    venvs: Vec<String>,
+    #[clap(long, short, action, help = "Do not ask for confirmation")]
+    yes: bool,
+    #[clap(long, action, help = "Show what would be deleted without deleting")]
+    dry_run: bool,
+    #[clap(
+        long,
+        action,
+        help = "Delete permanently instead of moving to the trash"
+    )]
+    permanent: bool,
+    #[clap(
+        long,
+        help = "Delete only venvs with python version matching the prefix (e.g. 3.11)"
+    )]
+    python: Option<String>,
+    #[clap(
+        long,
+        value_parser = humantime::parse_duration,
+        help = "Delete only venvs not used or modified for this time (e.g. 30d)"
+    )]
+    older_than: Option<Duration>,
+    #[clap(long, action, help = "Delete only venvs not used by any known project")]
+    unused: bool,
+}
+
+fn is_pattern(name: &str) -> bool {
+    name.contains(['*', '?', '['])
+}
+
+// Patterns match either the full name (version/name) or only its last component
+fn matches(pattern: &Pattern, name: &str) -> bool {
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::new()
+    };
+    pattern.matches_with(name, options)
+        || name
+            .rsplit_once('/')
+            .is_some_and(|(_, short)| pattern.matches_with(short, options))
+}
+
+fn write_preview(line: &str) {
+    // STDOUT can be evaluated by the shell, so the preview goes to STDERR
+    io::stderr()
+        .write_all(format!("{line}\n").as_bytes())
+        .unwrap_or(());
 }
 
 impl DeleteCommand {
+    fn has_filters(&self) -> bool {
+        self.python.is_some() || self.older_than.is_some() || self.unused
+    }
+
+    fn select(&self) -> Result<Vec<(String, PathBuf)>> {
+        let patterns = self
+            .venvs
+            .iter()
+            .map(|venv| {
+                if !is_pattern(venv) {
+                    Rsenv::validate_name(venv)?;
+                }
+                Pattern::new(venv).with_context(|| format!("Unable to parse glob {venv}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let root = Rsenv.root_dir()?;
+        let now = SystemTime::now();
+        Ok(Rsenv
+            .list()
+            .into_iter()
+            .sorted()
+            .filter(|name| patterns.is_empty() || patterns.iter().any(|p| matches(p, name)))
+            .map(|name| {
+                let path = root.join(&name);
+                let path = fs::canonicalize(&path).unwrap_or(path);
+                (name, path)
+            })
+            .filter(|(_, path)| {
+                self.python.as_ref().is_none_or(|prefix| {
+                    PyvenvCfg::read(path)
+                        .ok()
+                        .and_then(|cfg| cfg.version().map(|v| version_matches(v, prefix)))
+                        .unwrap_or(false)
+                })
+            })
+            .filter(|(_, path)| {
+                self.older_than.is_none_or(|limit| {
+                    last_used(path)
+                        .and_then(|time| now.duration_since(time).ok())
+                        .is_some_and(|age| age >= limit)
+                })
+            })
+            .filter(|(name, _)| !self.unused || Projects::using_venv(name).is_empty())
+            .collect())
+    }
+
+    fn delete_many(&self) -> Result<()> {
+        let selected = self.select()?;
+        if selected.is_empty() {
+            info!("No virtual environments match");
+            return Ok(());
+        }
+        let mut total = 0;
+        for (name, path) in selected.iter() {
+            let size = dir_size(path);
+            total += size;
+            write_preview(&format!("\t{name}\t{}", format_size(size)));
+            if is_active(path) {
+                warn!("{name} is active in the current shell");
+            }
+            for marker in Projects::using_venv(name) {
+                warn!("{} is still used by {}", name, marker.display());
+            }
+            for alias in Aliases::pointing_to(name) {
+                warn!("Alias {alias} points to {name}");
+            }
+        }
+        let question = format!(
+            "{} {} virtual environments ({})?",
+            if self.permanent {
+                "Permanently delete"
+            } else {
+                "Move to trash"
+            },
+            selected.len(),
+            format_size(total)
+        );
+        if self.dry_run {
+            info!("Dry run: {question}");
+            return Ok(());
+        }
+        if !self.yes && !confirm(&question)? {
+            bail!("Deletion cancelled");
+        }
+        let mut deactivate = false;
+        for (name, path) in selected.iter() {
+            deactivate |= is_active(path);
+            Rsenv.delete(name, self.permanent)?;
+        }
+        if deactivate {
+            VirtualEnvironment::deactivate(true)?;
+        }
+        Ok(())
+    }
+
+    fn delete_one(&self, venv: &String) -> Result<()> {
+        let backend = find_backend(venv)?;
+        info!("{} is managed by {}", venv, backend.backend());
+        let path = backend.path(Some(venv))?;
+        let path = fs::canonicalize(&path).unwrap_or(path);
+        let active = is_active(&path);
+        if active {
+            warn!("{} is active in the current shell", venv);
+        }
+        let name = backend.resolve(venv);
+        for marker in Projects::using_venv(&name) {
+            warn!("{} is still used by {}", venv, marker.display());
+        }
+        for alias in Aliases::pointing_to(&name) {
+            warn!("Alias {alias} points to {name}");
+        }
+        let permanent = self.permanent || !backend.supports_trash();
+        let question = format!(
+            "{} {} ({}, {})?",
+            if permanent {
+                "Permanently delete"
+            } else {
+                "Move to trash"
+            },
+            venv,
+            path.display(),
+            format_size(dir_size(&path))
+        );
+        if self.dry_run {
+            info!("Dry run: {question}");
+            return Ok(());
+        }
+        if !self.yes && !confirm(&question)? {
+            bail!("Deletion of {} cancelled", venv);
+        }
+        backend.delete(venv, permanent)?;
+        if active {
+            VirtualEnvironment::deactivate(true)?;
+        }
+        Ok(())
+    }
+
     pub fn execute(&self) -> Result<()> {
-        Rsenv.delete(self.venv.clone())
+        match self.venvs.as_slice() {
+            [] if !self.has_filters() => {
+                bail!("Specify virtual environment names, patterns or filters")
+            }
+            [venv] if !is_pattern(venv) && !self.has_filters() => self.delete_one(venv),
+            _ => self.delete_many(),
+        }
     }
 }
@@ -0,0 +1,75 @@
+use std::{fs, io, io::Write};
+
+use anyhow::Result;
+use clap::Parser;
+use itertools::Itertools;
+
+use crate::virtualenv::{
+    find_backend,
+    rsenv::Rsenv,
+    traits::VirtualEnvCompatible,
+    usage::{disk_usage, DiskUsage},
+    utils::{format_size, format_table},
+};
+
+#[derive(Debug, Parser)]
+pub struct DuCommand {
+    #[clap(help = "Virtual environment names. Defaults to all venvs of the rsvenv store")]
+    venvs: Vec<String>,
+    #[clap(long, help = "Show only the N largest venvs")]
+    top: Option<usize>,
+}
+
+impl DuCommand {
+    pub fn execute(&self) -> Result<()> {
+        let names = if self.venvs.is_empty() {
+            Rsenv.list().into_iter().sorted().collect_vec()
+        } else {
+            self.venvs.clone()
+        };
+        let paths = names
+            .iter()
+            .map(|name| {
+                // Pyenv venvs are reached through symlinks in the versions directory
+                let path = find_backend(name)?.path(Some(name))?;
+                Ok(fs::canonicalize(&path).unwrap_or(path))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let usage = disk_usage(&paths.iter().map(|path| path.as_path()).collect_vec());
+
+        let total = usage
+            .iter()
+            .fold(DiskUsage::default(), |total, usage| DiskUsage {
+                total: total.total + usage.total,
+                site_packages: total.site_packages + usage.site_packages,
+                cache: total.cache + usage.cache,
+            });
+        let row = |name: &str, usage: &DiskUsage| {
+            vec![
+                name.to_string(),
+                format_size(usage.total),
+                format_size(usage.site_packages),
+                format_size(usage.cache),
+            ]
+        };
+        let rows = std::iter::once(
+            ["NAME", "TOTAL", "SITE-PACKAGES", "CACHE"]
+                .map(String::from)
+                .to_vec(),
+        )
+        .chain(
+            names
+                .iter()
+                .zip(&usage)
+                .sorted_by(|a, b| b.1.total.cmp(&a.1.total).then(a.0.cmp(b.0)))
+                .take(self.top.unwrap_or(usize::MAX))
+                .map(|(name, usage)| row(name, usage)),
+        )
+        .chain(std::iter::once(row("total", &total)))
+        .collect_vec();
+        io::stdout()
+            .write_all(format_table(&rows).as_bytes())
+            .unwrap_or(());
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use std::{convert::Infallible, os::unix::process::CommandExt, process};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use simplelog::{debug, error};
+
+use crate::virtualenv::{resolve_venv, utils::mark_used, venv_command};
+
+#[derive(Debug, Parser)]
+pub struct ExecCommand {
+    #[clap(help = "Virtual environment name. Defaults to the venv of the current directory")]
+    venv: Option<String>,
+    #[clap(last = true, required = true, help = "Command with arguments")]
+    command: Vec<String>,
+}
+
+impl ExecCommand {
+    fn run(&self) -> Result<Infallible> {
+        let path = resolve_venv(self.venv.as_ref())?;
+        mark_used(&path);
+        debug!("Executing {:?} in {}", self.command, path.display());
+        // exec replaces the process, so the exit code is the one of the command
+        let error = venv_command(&path, &self.command[0])
+            .args(&self.command[1..])
+            .exec();
+        Err(error).with_context(|| format!("Unable to execute {}", self.command[0]))
+    }
+
+    pub fn execute(&self) -> Result<()> {
+        // Scripts rely on the exit code, so failing to start the command must not exit with 0
+        let Err(e) = self.run();
+        error!("{e}");
+        debug!("{e:?}");
+        process::exit(127);
+    }
+}
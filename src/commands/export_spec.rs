@@ -0,0 +1,48 @@
+use std::{fs, io, io::Write, path::PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use itertools::Itertools;
+
+use crate::virtualenv::{
+    distributions::installed,
+    find_backend,
+    python::parse_version,
+    pyvenv_cfg::PyvenvCfg,
+    spec::{Spec, SpecFormat},
+};
+
+#[derive(Debug, Parser)]
+pub struct ExportSpecCommand {
+    #[clap(help = "Virtual environment name")]
+    venv: String,
+    #[clap(long, short, value_enum, default_value = "requirements")]
+    format: SpecFormat,
+    #[clap(long, short, help = "Output file. Defaults to STDOUT")]
+    output: Option<PathBuf>,
+}
+
+impl ExportSpecCommand {
+    pub fn execute(&self) -> Result<()> {
+        let path = find_backend(&self.venv)?.path(Some(&self.venv))?;
+        let cfg = PyvenvCfg::read(&path)?;
+        let python_version =
+            parse_version(cfg.version().context("No python version in pyvenv.cfg")?)
+                .iter()
+                .join(".");
+        let distributions = installed(&path)?;
+        let spec = Spec {
+            name: self.venv.rsplit('/').next().unwrap_or(&self.venv),
+            python_version: &python_version,
+            distributions: &distributions,
+        }
+        .render(self.format);
+        match &self.output {
+            Some(output) => fs::write(output, spec)
+                .with_context(|| format!("Unable to write {}", output.display())),
+            None => io::stdout()
+                .write_all(spec.as_bytes())
+                .context("Unable to write to STDOUT"),
+        }
+    }
+}
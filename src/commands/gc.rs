@@ -0,0 +1,98 @@
+use std::fs;
+use std::io;
+use std::io::Write;
+
+use crate::virtualenv::{
+    health::{persists, scan},
+    locks::Lock,
+    repair::repair,
+    rsenv::Rsenv,
+    traits::VirtualEnvCompatible,
+    utils::confirm,
+};
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use itertools::Itertools;
+use simplelog::{debug, error, info, warn};
+
+#[derive(Debug, Parser)]
+pub struct GcCommand {
+    #[clap(
+        long,
+        action,
+        help = "Repair broken and orphaned venvs using an interpreter of the same minor version"
+    )]
+    repair: bool,
+    #[clap(long, action, help = "Remove broken, orphaned and half-created venvs")]
+    remove: bool,
+    #[clap(long, short, action, help = "Do not ask for confirmation")]
+    yes: bool,
+}
+
+impl GcCommand {
+    pub fn execute(&self) -> Result<()> {
+        // Venvs locked by another process are being created or changed rather than broken
+        let mut problems = scan(&Rsenv.root_dir()?)
+            .into_iter()
+            .filter_map(|(name, path, problem)| match Lock::try_venv(&name) {
+                Ok(lock) => Some((name, path, problem, lock)),
+                Err(e) => {
+                    debug!("Skipping {name}: {e}");
+                    None
+                }
+            })
+            .collect_vec();
+        for (name, _, problem, _) in problems.iter() {
+            io::stdout()
+                .write_all(format!("{name}\t{problem}\n").as_bytes())
+                .unwrap_or(());
+        }
+        if self.repair {
+            problems.retain(|(name, path, problem, _)| {
+                if !problem.repairable() {
+                    return true;
+                }
+                match repair(path, None) {
+                    Ok(_) => {
+                        info!("Repaired {name}");
+                        false
+                    }
+                    Err(e) => {
+                        error!("Unable to repair {name}: {e}");
+                        true
+                    }
+                }
+            });
+        }
+        if !self.remove || problems.is_empty() {
+            return Ok(());
+        }
+        let question = format!("Permanently delete {} directories?", problems.len());
+        if !self.yes && !confirm(&question)? {
+            bail!("Garbage collection cancelled");
+        }
+        // Locks of the scan are released, as the store lock is taken first. Under the store lock
+        // no venv is being reserved, so directories still broken or empty can be removed
+        let problems = problems
+            .into_iter()
+            .map(|(name, path, problem, _)| (name, path, problem))
+            .collect_vec();
+        let _store_lock = Lock::store()?;
+        for (name, path, problem) in problems {
+            let _lock = match Lock::try_venv(&name) {
+                Ok(lock) => lock,
+                Err(e) => {
+                    warn!("Skipping {name}: {e}");
+                    continue;
+                }
+            };
+            if !persists(&path, &problem) {
+                warn!("Skipping {name}: it has changed since the scan");
+                continue;
+            }
+            fs::remove_dir_all(&path).with_context(|| format!("Unable to delete {name}"))?;
+            info!("Deleted {name}");
+        }
+        Ok(())
+    }
+}
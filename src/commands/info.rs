@@ -0,0 +1,141 @@
+use std::{fs, io, io::Write, path::PathBuf, time::SystemTime};
+
+use anyhow::Result;
+use clap::Parser;
+use itertools::Itertools;
+use serde::Serializer;
+use serde_derive::Serialize;
+
+use crate::virtualenv::{
+    aliases::Aliases,
+    distributions::installed,
+    find_backend,
+    health::check,
+    inventory::serialize_time,
+    projects::Projects,
+    pyvenv_cfg::PyvenvCfg,
+    utils::{dir_size, format_size, format_table, is_virtualenv, last_used},
+};
+
+#[derive(Debug, Parser)]
+pub struct InfoCommand {
+    #[clap(help = "Virtual environment name")]
+    venv: String,
+    #[clap(long, action, help = "Print as JSON")]
+    json: bool,
+}
+
+// pyvenv.cfg keeps the order of the file
+fn serialize_cfg<S: Serializer>(cfg: &PyvenvCfg, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(cfg.entries.iter().map(|(key, value)| (key, value)))
+}
+
+#[derive(Debug, Serialize)]
+struct VenvInfo {
+    name: String,
+    backend: &'static str,
+    path: PathBuf,
+    python: Option<String>,
+    interpreter: Option<PathBuf>,
+    interpreter_exists: bool,
+    problem: Option<String>,
+    packages: usize,
+    size: u64,
+    #[serde(serialize_with = "serialize_time")]
+    created: Option<SystemTime>,
+    #[serde(serialize_with = "serialize_time")]
+    last_used: Option<SystemTime>,
+    aliases: Vec<String>,
+    projects: Vec<PathBuf>,
+    #[serde(serialize_with = "serialize_cfg")]
+    pyvenv_cfg: PyvenvCfg,
+}
+
+fn format_time(time: Option<SystemTime>) -> String {
+    time.map(|time| humantime::format_rfc3339_seconds(time).to_string())
+        .unwrap_or("-".into())
+}
+
+impl VenvInfo {
+    fn render(&self) -> String {
+        let rows = vec![
+            ("name", self.name.clone()),
+            ("backend", self.backend.to_string()),
+            ("path", self.path.display().to_string()),
+            ("python", self.python.clone().unwrap_or("-".into())),
+            (
+                "interpreter",
+                match &self.interpreter {
+                    Some(interpreter) if self.interpreter_exists => {
+                        interpreter.display().to_string()
+                    }
+                    Some(interpreter) => format!("{} (missing)", interpreter.display()),
+                    None => "-".into(),
+                },
+            ),
+            ("problem", self.problem.clone().unwrap_or("-".into())),
+            ("packages", self.packages.to_string()),
+            ("size", format_size(self.size)),
+            ("created", format_time(self.created)),
+            ("last used", format_time(self.last_used)),
+            ("aliases", self.aliases.join(", ")),
+            (
+                "projects",
+                self.projects.iter().map(|p| p.display()).join(", "),
+            ),
+        ];
+        let mut result = format_table(
+            &rows
+                .into_iter()
+                .map(|(key, value)| vec![format!("{key}:"), value])
+                .collect_vec(),
+        );
+        result.push_str("pyvenv.cfg:\n");
+        for (key, value) in &self.pyvenv_cfg.entries {
+            result.push_str(&format!("\t{key} = {value}\n"));
+        }
+        result
+    }
+}
+
+impl InfoCommand {
+    pub fn execute(&self) -> Result<()> {
+        let backend = find_backend(&self.venv)?;
+        let path = backend.path(Some(&self.venv))?;
+        is_virtualenv(&path)?;
+        let path = fs::canonicalize(&path).unwrap_or(path);
+        let pyvenv_cfg = PyvenvCfg::read(&path)?;
+        let python = path.join("bin").join("python");
+        let interpreter = fs::canonicalize(&python)
+            .ok()
+            .or_else(|| fs::read_link(&python).ok());
+        let name = backend.resolve(&self.venv);
+        let aliases = Aliases::pointing_to(&name);
+        let projects = Projects::using_venv(&name);
+        let info = VenvInfo {
+            backend: backend.backend(),
+            python: pyvenv_cfg.version().map(String::from),
+            interpreter_exists: fs::metadata(&python).is_ok(),
+            interpreter,
+            problem: check(&path).map(|problem| problem.to_string()),
+            packages: installed(&path).map(|d| d.len()).unwrap_or_default(),
+            size: dir_size(&path),
+            created: fs::metadata(path.join("pyvenv.cfg"))
+                .and_then(|metadata| metadata.created().or_else(|_| metadata.modified()))
+                .ok(),
+            last_used: last_used(&path),
+            aliases,
+            projects,
+            pyvenv_cfg,
+            path,
+            name,
+        };
+        let output = if self.json {
+            format!("{}\n", serde_json::to_string_pretty(&info)?)
+        } else {
+            info.render()
+        };
+        io::stdout().write_all(output.as_bytes()).unwrap_or(());
+        Ok(())
+    }
+}
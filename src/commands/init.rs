@@ -1,14 +1,26 @@
-use crate::shell::SupportedShell;
+use crate::shell::{starship, SupportedShell};
 use anyhow::{Context, Result};
 use clap::Parser;
 use std::io;
 use std::io::Write;
 
 #[derive(Debug, Parser)]
-pub struct Command {}
+pub struct Command {
+    #[clap(
+        long,
+        action,
+        help = "Print the starship custom module instead of the shell hook"
+    )]
+    starship: bool,
+}
 
 impl Command {
     pub fn execute(&self) -> Result<()> {
+        if self.starship {
+            return io::stdout()
+                .write_all(starship::MODULE.as_bytes())
+                .context("Unable to write starship module to STDOUT!");
+        }
         let shell = SupportedShell::new().context("Unable to detect current shell")?;
 
         let hook = shell.get_hook();
@@ -1,36 +1,140 @@
-use crate::virtualenv::{pyenv::Pyenv, rsenv::Rsenv, traits::VirtualEnvCompatible};
+use crate::virtualenv::{
+    inventory::{collect, VenvEntry},
+    locks::Lock,
+    projects::Projects,
+    pyvenv_cfg::version_matches,
+    utils::{format_size, format_table, get_current_dir},
+    BACKENDS,
+};
 use anyhow::Result;
-use clap::Parser;
-use std::collections::HashSet;
+use clap::{builder::PossibleValuesParser, Parser, ValueEnum};
+use itertools::Itertools;
+use simplelog::warn;
 use std::io;
 use std::io::Write;
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ListFormat {
+    Plain,
+    Table,
+    Json,
+}
+
 #[derive(Debug, Parser)]
-pub struct ListCommand {}
+pub struct ListCommand {
+    #[clap(long, short, value_enum, default_value = "plain")]
+    format: ListFormat,
+    #[clap(
+        long,
+        value_parser = PossibleValuesParser::new(["rsenv", "pyenv", "local"]),
+        help = "Show only venvs of the backend"
+    )]
+    backend: Option<String>,
+    #[clap(
+        long,
+        help = "Show only venvs with python version matching the prefix (e.g. 3.11)"
+    )]
+    python: Option<String>,
+}
+
+fn write(content: &str) {
+    io::stdout().write_all(content.as_bytes()).unwrap_or(());
+}
+
+// Marks the active and the current directory venv the way `pyenv versions` does
+fn plain_line(entry: &VenvEntry) -> String {
+    let mut notes = vec![];
+    if entry.active {
+        notes.push("active".to_string());
+    }
+    if entry.bound {
+        let cwd = get_current_dir().unwrap_or_default();
+        let source = match Projects::marker(&cwd) {
+            Some((marker, _)) if entry.backend != "local" => marker,
+            _ => cwd,
+        };
+        notes.push(format!("set by {}", source.display()));
+    }
+    if notes.is_empty() {
+        return format!("\t  {}\n", entry.name);
+    }
+    format!("\t* {} ({})\n", entry.name, notes.join(", "))
+}
 
-fn print_venvs(rsenv_venvs: HashSet<String>) {
-    for name in rsenv_venvs {
-        io::stdout().write_all("\t".as_bytes()).unwrap_or(());
-        io::stdout().write_all(name.as_bytes()).unwrap_or(());
-        io::stdout().write_all("\n".as_bytes()).unwrap_or(());
+fn print_plain(entries: &[VenvEntry]) {
+    for entries in entries.chunk_by(|a, b| a.backend == b.backend) {
+        let mut heading = entries[0].backend.to_string();
+        heading[..1].make_ascii_uppercase();
+        write(&format!("{heading} environments:\n"));
+        for entry in entries {
+            write(&plain_line(entry));
+        }
     }
 }
 
+fn print_table(entries: &[VenvEntry]) {
+    let header = [
+        "BACKEND",
+        "NAME",
+        "PATH",
+        "PYTHON",
+        "SIZE",
+        "LAST USED",
+        "ACTIVE",
+        "BOUND",
+    ]
+    .map(String::from);
+    let rows = entries
+        .iter()
+        .map(|entry| {
+            [
+                entry.backend.to_string(),
+                entry.name.clone(),
+                entry.path.display().to_string(),
+                entry.python.clone().unwrap_or("-".into()),
+                entry.size.map(format_size).unwrap_or("-".into()),
+                entry
+                    .last_used
+                    .map(|time| humantime::format_rfc3339_seconds(time).to_string())
+                    .unwrap_or("-".into()),
+                if entry.active { "*" } else { "" }.to_string(),
+                if entry.bound { "*" } else { "" }.to_string(),
+            ]
+        })
+        .collect_vec();
+    write(&format_table(
+        &std::iter::once(header.to_vec())
+            .chain(rows.into_iter().map(Vec::from))
+            .collect_vec(),
+    ));
+}
+
 impl ListCommand {
     pub fn execute(&self) -> Result<()> {
-        let rsenv_venvs = Rsenv.list();
-        if !rsenv_venvs.is_empty() {
-            io::stdout()
-                .write_all("Rsenv environments:\n".as_bytes())
-                .unwrap_or(());
-            print_venvs(rsenv_venvs);
-        }
-        let pyenv_venvs = Pyenv.list();
-        if !pyenv_venvs.is_empty() {
-            io::stdout()
-                .write_all("Pyenv environments:\n".as_bytes())
-                .unwrap_or(());
-            print_venvs(pyenv_venvs);
+        // Listing while another process changes the store is still useful, so the lock is optional
+        let _lock = Lock::store_shared().map_err(|e| warn!("{e}")).ok();
+        let backends = BACKENDS
+            .into_iter()
+            .filter(|backend| self.backend.as_ref().is_none_or(|b| b == backend.backend()))
+            .collect_vec();
+        let with_size = !matches!(self.format, ListFormat::Plain);
+        let entries = collect(&backends, with_size)
+            .into_iter()
+            .filter(|entry| match &self.python {
+                Some(prefix) => entry
+                    .python
+                    .as_ref()
+                    .is_some_and(|version| version_matches(version, prefix)),
+                None => true,
+            })
+            .collect_vec();
+        match self.format {
+            ListFormat::Plain => print_plain(&entries),
+            ListFormat::Table => print_table(&entries),
+            ListFormat::Json => {
+                let json = serde_json::to_string_pretty(&entries)?;
+                write(&format!("{json}\n"));
+            }
         }
         Ok(())
     }
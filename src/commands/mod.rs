@@ -1,9 +1,28 @@
 pub mod activate;
+pub mod alias;
+pub mod archive;
 pub mod chdir_hook;
+pub mod clone;
 pub mod create;
+pub mod current;
 pub mod deactivate;
 pub mod delete;
+pub mod du;
+pub mod exec;
+pub mod export_spec;
+pub mod gc;
+pub mod info;
 pub mod init;
 pub mod install;
 pub mod list;
+pub mod presets;
+pub mod prompt;
+pub mod rename;
+pub mod repair;
+pub mod restore;
+pub mod shell;
+pub mod snapshot;
+pub mod trash;
+pub mod upgrade;
 pub mod use_command;
+pub mod which;
@@ -0,0 +1,29 @@
+use crate::configuration::SETTINGS;
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use itertools::Itertools;
+use std::io;
+use std::io::Write;
+
+#[derive(Debug, Parser)]
+pub struct PresetsCommand {}
+
+impl PresetsCommand {
+    pub fn execute(&self) -> Result<()> {
+        let settings = SETTINGS
+            .read()
+            .map_err(|e| anyhow!("Unable to read presets from SETTINGS: {e:?}"))?;
+        for (name, preset) in settings.presets.iter().sorted_by_key(|(name, _)| *name) {
+            let mut line = format!("{name}\t{}", preset.python);
+            if !preset.venv_options.is_empty() {
+                line.push_str(&format!("\t{}", preset.venv_options.join(" ")));
+            }
+            if !preset.packages.is_empty() {
+                line.push_str(&format!("\t[{}]", preset.packages.join(", ")));
+            }
+            line.push('\n');
+            io::stdout().write_all(line.as_bytes()).unwrap_or(());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use std::{
+    fs, io,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use serde_derive::Serialize;
+use tinytemplate::TinyTemplate;
+
+use crate::virtualenv::{
+    detect_backend, find_by_path, pyenv::Pyenv, pyvenv_cfg::PyvenvCfg, traits::VirtualEnvCompatible,
+};
+
+#[derive(Debug, Parser)]
+pub struct PromptCommand {
+    #[clap(
+        long,
+        short,
+        default_value = "{name} ({python})",
+        help = "Segment format with {name}, {python}, {backend} and {path} fields"
+    )]
+    format: String,
+}
+
+#[derive(Serialize)]
+struct PromptContext {
+    name: String,
+    python: String,
+    backend: String,
+    path: String,
+}
+
+// Fields of the venv at the path as given, e.g. VIRTUAL_ENV
+fn context(given: &Path) -> PromptContext {
+    let path = fs::canonicalize(given).unwrap_or(given.to_path_buf());
+    let basename = |path: &Path| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or(given.display().to_string())
+    };
+    let (backend, name) = match find_by_path(&path) {
+        // Pyenv venvs are activated through the versions/<name> symlink by their short name
+        Some((backend, _)) if backend.backend() == Pyenv.backend() && path != given => {
+            (backend.backend().to_string(), basename(given))
+        }
+        Some((backend, name)) => (backend.backend().to_string(), name),
+        None => (String::new(), basename(&path)),
+    };
+    PromptContext {
+        name,
+        python: PyvenvCfg::read(&path)
+            .ok()
+            .and_then(|cfg| cfg.version().map(String::from))
+            .unwrap_or_default(),
+        backend,
+        path: path.display().to_string(),
+    }
+}
+
+impl PromptCommand {
+    // Prompts are rendered on every command, so neither python nor the shell detection is spawned
+    pub fn execute(&self) -> Result<()> {
+        let active = std::env::var_os("VIRTUAL_ENV").map(PathBuf::from);
+        let bound = detect_backend().and_then(|backend| backend.path(None).ok());
+        let (context, mismatch) = match (&active, &bound) {
+            (None, None) => return Ok(()),
+            (None, Some(bound)) => (context(bound), Some("bound venv not active")),
+            (Some(active), bound) => {
+                let context = context(active);
+                let differs = bound.as_ref().is_some_and(|bound| {
+                    fs::canonicalize(bound)
+                        .unwrap_or(bound.clone())
+                        .display()
+                        .to_string()
+                        != context.path
+                });
+                (context, differs.then_some("bound venv differs from active"))
+            }
+        };
+        let mut tt = TinyTemplate::new();
+        tt.set_default_formatter(&tinytemplate::format_unescaped);
+        tt.add_template("prompt", &self.format)
+            .context("Unable to parse prompt format")?;
+        let mut segment = tt
+            .render("prompt", &context)
+            .context("Unable to render prompt format")?;
+        if let Some(mismatch) = mismatch {
+            segment.push_str(&format!(" [{mismatch}]"));
+        }
+        io::stdout()
+            .write_all(format!("{segment}\n").as_bytes())
+            .unwrap_or(());
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+use anyhow::Result;
+use clap::Parser;
+use simplelog::warn;
+
+use crate::virtualenv::{projects::Projects, rsenv::Rsenv};
+
+#[derive(Debug, Parser)]
+pub struct RenameCommand {
+    #[clap(help = "Virtual environment name")]
+    old: String,
+    #[clap(help = "New name. Without python version the version of the old name is kept")]
+    new: String,
+    #[clap(
+        long,
+        action,
+        help = "Update marker files of the projects that use the virtual environment"
+    )]
+    update_projects: bool,
+}
+
+impl RenameCommand {
+    pub fn execute(&self) -> Result<()> {
+        let new = Rsenv.rename(&self.old, &self.new)?;
+        if self.update_projects {
+            Projects::rebind(&self.old, &new)?;
+        } else {
+            for marker in Projects::using(&self.old) {
+                warn!("{} still references {}", marker.display(), self.old);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+use anyhow::Result;
+use clap::Parser;
+use simplelog::info;
+
+use crate::virtualenv::{find_backend, locks::Lock, repair::repair};
+
+#[derive(Debug, Parser)]
+pub struct RepairCommand {
+    #[clap(help = "Virtual environment name")]
+    venv: String,
+    #[clap(
+        long,
+        help = "Python executable or version of the same minor series (e.g. 3.11.9)"
+    )]
+    python: Option<String>,
+}
+
+impl RepairCommand {
+    pub fn execute(&self) -> Result<()> {
+        let backend = find_backend(&self.venv)?;
+        let _lock = match backend.backend() {
+            "rsenv" => Some(Lock::venv(&backend.resolve(&self.venv))?),
+            _ => None,
+        };
+        let path = backend.path(Some(&self.venv))?;
+        let interpreter = repair(&path, self.python.as_deref())?;
+        info!("Repaired {} with python {}", self.venv, interpreter.version);
+        Ok(())
+    }
+}
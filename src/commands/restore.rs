@@ -0,0 +1,15 @@
+use crate::virtualenv::rsenv::Rsenv;
+use anyhow::Result;
+use clap::Parser;
+
+#[derive(Debug, Parser)]
+pub struct RestoreCommand {
+    #[clap(help = "Name of the deleted virtual environment")]
+    venv: String,
+}
+
+impl RestoreCommand {
+    pub fn execute(&self) -> Result<()> {
+        Rsenv.restore(&self.venv)
+    }
+}
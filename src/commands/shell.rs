@@ -0,0 +1,48 @@
+use std::{fs, process};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use simplelog::info;
+
+use crate::shell::SupportedShell;
+use crate::virtualenv::{
+    inactive_command, resolve_venv,
+    utils::{get_rsvenv_home, mark_used},
+};
+
+#[derive(Debug, Parser)]
+pub struct ShellCommand {
+    #[clap(help = "Virtual environment name")]
+    venv: String,
+}
+
+impl ShellCommand {
+    pub fn execute(&self) -> Result<()> {
+        let shell = SupportedShell::new()?;
+        let path = resolve_venv(Some(&self.venv))?;
+        mark_used(&path);
+        info!(
+            "Starting {} in {}, exit to leave it",
+            shell.program(),
+            self.venv
+        );
+        // The subshell activates the venv itself, so deactivate and the prompt work, and
+        // must not deactivate it or reactivate the one of the parent session from the hook
+        let rc_dir = get_rsvenv_home()?.join(format!("shell-{}", process::id()));
+        fs::create_dir_all(&rc_dir).context("Unable to create temporary directory")?;
+        let mut command = inactive_command(shell.program());
+        command
+            .env_remove("RSVENV_ACTIVATE_PATH")
+            .env_remove("RSVENV_DEACTIVATE_PATH");
+        let status = shell
+            .configure_subshell(&mut command, &path, &rc_dir)
+            .and_then(|_| {
+                command
+                    .status()
+                    .with_context(|| format!("Unable to start {}", shell.program()))
+            });
+        fs::remove_dir_all(&rc_dir).unwrap_or_default();
+        let status = status?;
+        process::exit(status.code().unwrap_or(1));
+    }
+}
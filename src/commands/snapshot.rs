@@ -0,0 +1,90 @@
+use std::io;
+use std::io::Write;
+
+use anyhow::{bail, Result};
+use clap::Parser;
+
+use crate::virtualenv::{
+    locks::Lock,
+    rsenv::Rsenv,
+    snapshots::Snapshots,
+    traits::VirtualEnvCompatible,
+    utils::{confirm, dir_size, format_size},
+};
+
+fn venv_path(name: &String) -> Result<std::path::PathBuf> {
+    Rsenv::validate_name(name)?;
+    if !Rsenv.list().contains(name) {
+        bail!("{name} is not managed by rsenv");
+    }
+    Rsenv.path(Some(name))
+}
+
+#[derive(Debug, Parser)]
+pub struct SnapshotCommand {
+    #[clap(help = "Virtual environment name")]
+    venv: String,
+    #[clap(help = "Snapshot label. Defaults to the current time")]
+    label: Option<String>,
+}
+
+impl SnapshotCommand {
+    pub fn execute(&self) -> Result<()> {
+        let path = venv_path(&self.venv)?;
+        let _lock = Lock::venv(&self.venv)?;
+        Snapshots::new(&self.venv).create(&path, self.label.as_ref())?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Parser)]
+pub struct RollbackCommand {
+    #[clap(help = "Virtual environment name")]
+    venv: String,
+    #[clap(help = "Snapshot label. Defaults to the latest snapshot")]
+    label: Option<String>,
+    #[clap(long, short, action, help = "Do not ask for confirmation")]
+    yes: bool,
+}
+
+impl RollbackCommand {
+    pub fn execute(&self) -> Result<()> {
+        let path = venv_path(&self.venv)?;
+        let snapshots = Snapshots::new(&self.venv);
+        let snapshot = snapshots.get(self.label.as_ref())?;
+        let question = format!("Replace {} with snapshot {}?", self.venv, snapshot.label);
+        if !self.yes && !confirm(&question)? {
+            bail!("Rollback of {} cancelled", self.venv);
+        }
+        let _lock = Lock::venv(&self.venv)?;
+        snapshots.rollback(&path, Some(&snapshot.label))
+    }
+}
+
+#[derive(Debug, Parser)]
+pub struct SnapshotsCommand {
+    #[clap(help = "Virtual environment name")]
+    venv: String,
+    #[clap(long, help = "Delete the snapshot with this label")]
+    delete: Option<String>,
+}
+
+impl SnapshotsCommand {
+    pub fn execute(&self) -> Result<()> {
+        Rsenv::validate_name(&self.venv)?;
+        let snapshots = Snapshots::new(&self.venv);
+        if let Some(label) = &self.delete {
+            return snapshots.delete(label);
+        }
+        for snapshot in snapshots.list() {
+            let line = format!(
+                "{}\t{}\t{}\n",
+                snapshot.label,
+                humantime::format_rfc3339_seconds(snapshot.created_at),
+                format_size(dir_size(&snapshot.path))
+            );
+            io::stdout().write_all(line.as_bytes()).unwrap_or(());
+        }
+        Ok(())
+    }
+}
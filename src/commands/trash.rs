@@ -0,0 +1,60 @@
+use std::io;
+use std::io::Write;
+use std::time::Duration;
+
+use crate::virtualenv::{trash::Trash, utils::confirm};
+use anyhow::{bail, Result};
+use clap::{Parser, Subcommand};
+use simplelog::info;
+
+#[derive(Debug, Parser)]
+pub struct TrashCommand {
+    #[command(subcommand)]
+    command: TrashSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum TrashSubcommand {
+    #[clap(name = "list", about = "List deleted virtual environments")]
+    List,
+    #[clap(
+        name = "empty",
+        about = "Permanently delete virtual environments from the trash"
+    )]
+    Empty {
+        #[clap(
+            long,
+            value_parser = humantime::parse_duration,
+            help = "Delete only environments deleted earlier than this (e.g. 30d)"
+        )]
+        older_than: Option<Duration>,
+        #[clap(long, short, action, help = "Do not ask for confirmation")]
+        yes: bool,
+    },
+}
+
+impl TrashCommand {
+    pub fn execute(&self) -> Result<()> {
+        match &self.command {
+            TrashSubcommand::List => {
+                for entry in Trash::new().list() {
+                    let line = format!(
+                        "{}\t{}\n",
+                        humantime::format_rfc3339_seconds(entry.deleted_at),
+                        entry.name
+                    );
+                    io::stdout().write_all(line.as_bytes()).unwrap_or(());
+                }
+                Ok(())
+            }
+            TrashSubcommand::Empty { older_than, yes } => {
+                if !yes && !confirm("Permanently delete virtual environments from the trash?")? {
+                    bail!("Emptying the trash cancelled");
+                }
+                let removed = Trash::new().empty(*older_than)?;
+                info!("Removed {removed} virtual environments from the trash");
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::fs;
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use itertools::Itertools;
+use simplelog::{error, info};
+
+use crate::virtualenv::{
+    aliases::Aliases, distributions::installed, locks::Lock, projects::Projects,
+    python::PythonInterpreter, rsenv::Rsenv, traits::VirtualEnvCompatible,
+};
+
+#[derive(Debug, Parser)]
+pub struct UpgradeCommand {
+    #[clap(help = "Virtual environment name")]
+    venv: String,
+    #[clap(long, help = "Python executable or version (e.g. 3.12)")]
+    python: String,
+    #[clap(
+        long,
+        action,
+        help = "Keep the old virtual environment instead of moving it to the trash"
+    )]
+    keep: bool,
+}
+
+impl UpgradeCommand {
+    pub fn execute(&self) -> Result<()> {
+        Rsenv::validate_name(&self.venv)?;
+        let interpreter = PythonInterpreter::find(&self.python)?;
+        let short_name = match self.venv.split_once('/') {
+            Some((_, name)) => name.to_string(),
+            None => self.venv.clone(),
+        };
+        let new_name = format!("{}/{short_name}", interpreter.version);
+
+        // Both venvs are locked under the store lock, which is released before the slow part.
+        // The old venv stays usable (e.g. activated) while the packages are reinstalled
+        let store_lock = Lock::store()?;
+        let old_lock = Lock::venv_shared(&self.venv)?;
+        if !Rsenv.list().contains(&self.venv) {
+            bail!("{} is not managed by rsenv", self.venv);
+        }
+        let (new_path, _new_lock) = Rsenv.reserve(&new_name)?;
+        drop(store_lock);
+
+        Rsenv.create_reserved(&new_path, &interpreter, &[])?;
+        info!("Created venv {new_name}");
+        let path = Rsenv.path(Some(&self.venv))?;
+        let requirements = match installed(&path) {
+            Ok(distributions) => distributions
+                .into_iter()
+                .filter(|d| !d.is_bootstrap())
+                .map(|d| d.requirement())
+                .collect_vec(),
+            Err(e) => {
+                fs::remove_dir_all(&new_path).unwrap_or_default();
+                bail!(e);
+            }
+        };
+        info!(
+            "{} has {} packages to reinstall",
+            self.venv,
+            requirements.len()
+        );
+
+        if let Err(e) = Rsenv.install(&new_name, &requirements) {
+            error!("Reinstalling packages failed, keeping {}", self.venv);
+            Rsenv
+                .delete(&new_name, true)
+                .with_context(|| format!("Unable to clean up {new_name}"))?;
+            bail!(e);
+        }
+
+        drop(old_lock);
+        let _lock = Lock::venv(&self.venv)?;
+        if !Rsenv.list().contains(&self.venv) {
+            bail!(
+                "{} was removed during the upgrade, keeping {new_name}",
+                self.venv
+            );
+        }
+        Projects::rebind(&self.venv, &new_name)?;
+        Aliases::repoint(&self.venv, &new_name)?;
+        if !self.keep {
+            Rsenv.delete(&self.venv, false)?;
+        }
+        info!("Upgraded {} to {new_name}", self.venv);
+        Ok(())
+    }
+}
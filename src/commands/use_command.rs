@@ -10,7 +10,7 @@ pub struct UseCommand {
 }
 
 fn try_save(f: &dyn VirtualEnvCompatible, venv: String) -> Result<()> {
-    if f.list().contains(&venv) {
+    if f.contains(&venv) {
         f.save(&venv)?;
         return Ok(());
     }
@@ -0,0 +1,82 @@
+use std::{
+    fs, io,
+    io::Write,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use itertools::Itertools;
+
+use crate::virtualenv::{resolve_venv, BACKENDS};
+
+#[derive(Debug, Parser)]
+pub struct WhichCommand {
+    #[clap(help = "Command name")]
+    command: String,
+    #[clap(
+        long,
+        help = "Virtual environment name. Defaults to the active venv or the venv of the current directory"
+    )]
+    venv: Option<String>,
+    #[clap(
+        long,
+        action,
+        conflicts_with = "venv",
+        help = "Search in all virtual environments"
+    )]
+    all: bool,
+}
+
+fn find_executable(venv: &Path, command: &str) -> Option<PathBuf> {
+    let path = venv.join("bin").join(command);
+    fs::metadata(&path)
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .then_some(path)
+}
+
+impl WhichCommand {
+    fn search_all(&self) -> Vec<PathBuf> {
+        BACKENDS
+            .into_iter()
+            .flat_map(|backend| {
+                backend
+                    .list()
+                    .into_iter()
+                    .sorted()
+                    .filter_map(|name| backend.path(Some(&name)).ok())
+                    .collect_vec()
+            })
+            .filter_map(|venv| find_executable(&venv, &self.command))
+            .collect()
+    }
+
+    fn search_current(&self) -> Result<Vec<PathBuf>> {
+        let venv = match (&self.venv, std::env::var("VIRTUAL_ENV")) {
+            (None, Ok(active)) => PathBuf::from(active),
+            (name, _) => resolve_venv(name.as_ref())?,
+        };
+        match find_executable(&venv, &self.command) {
+            Some(path) => Ok(vec![path]),
+            None => bail!("{} not found in {}", self.command, venv.display()),
+        }
+    }
+
+    pub fn execute(&self) -> Result<()> {
+        let found = if self.all {
+            self.search_all()
+        } else {
+            self.search_current()?
+        };
+        if found.is_empty() {
+            bail!("{} not found in any virtual environment", self.command);
+        }
+        for path in found {
+            io::stdout()
+                .write_all(format!("{}\n", path.display()).as_bytes())
+                .unwrap_or(());
+        }
+        Ok(())
+    }
+}
@@ -1,20 +1,38 @@
-use config::{Config, ConfigError, Environment};
+use config::{Config, ConfigError, Environment, File};
 use lazy_static::lazy_static;
 use serde_derive::Deserialize;
+use std::collections::HashMap;
 use std::sync::RwLock;
 
+const CONFIG_FILE: &str = "~/.config/rsvenv/config";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Preset {
+    pub python: String,
+    #[serde(default)]
+    pub venv_options: Vec<String>,
+    #[serde(default)]
+    pub packages: Vec<String>,
+}
+
 #[derive(Debug, Deserialize)]
 #[allow(unused)]
 pub struct Settings {
     pub verbosity: i32,
     pub path: String,
+    pub lock_timeout: String,
+    #[serde(default)]
+    pub presets: HashMap<String, Preset>,
 }
 
 impl Settings {
     pub fn new() -> Result<Self, ConfigError> {
+        let config_file = shellexpand::tilde(CONFIG_FILE).to_string();
         let settings = Config::builder()
             .set_default("verbosity", 1)?
             .set_default("path", "~/.rsvenv".to_string())?
+            .set_default("lock_timeout", "10s".to_string())?
+            .add_source(File::with_name(&config_file).required(false))
             .add_source(Environment::with_prefix("RSVENV"))
             .build()?;
         settings.try_deserialize()
@@ -15,19 +15,22 @@ rsvenv () {
     SUBPARAMS=("${ALL_PARAMS[@]:1}")
     case $COMMAND in
     "activate")
-        eval "$($RS_VENV_PATH activate $SUBPARAMS)"
+        eval "$($RS_VENV_PATH activate "${SUBPARAMS[@]}")"
         ;;
     "deactivate")
-        eval "$($RS_VENV_PATH deactivate $SUBPARAMS)"
+        eval "$($RS_VENV_PATH deactivate "${SUBPARAMS[@]}")"
+        ;;
+    "create")
+        eval "$($RS_VENV_PATH create "${SUBPARAMS[@]}")"
         ;;
     "delete")
-        eval "$($RS_VENV_PATH delete $SUBPARAMS)"
+        eval "$($RS_VENV_PATH delete "${SUBPARAMS[@]}")"
         ;;
     "use")
-        eval "$($RS_VENV_PATH use $SUBPARAMS)"
+        eval "$($RS_VENV_PATH use "${SUBPARAMS[@]}")"
         ;;
     *)
-        $RS_VENV_PATH $ALL_PARAMS
+        $RS_VENV_PATH "${ALL_PARAMS[@]}"
         ;;
     esac
 }
@@ -49,3 +52,9 @@ deactivate
 "#;
 
 pub static INIT_COMMAND: &str = r#"eval "$(rsvenv init)""#;
+
+// --rcfile replaces ~/.bashrc, so it is sourced before the activation script
+pub static SUBSHELL_TEMPLATE: &str = r#"
+[ -f ~/.bashrc ] && source ~/.bashrc
+source {activate_path}
+"#;
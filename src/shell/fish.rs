@@ -12,7 +12,7 @@ function _rs_venv_virtualenv_hook --on-event fish_prompt;
 end
 
 function rsvenv
-    set eval_commands activate deactivate delete use
+    set eval_commands activate create deactivate delete use
     if contains $argv[1] $eval_commands
         $RS_VENV_PATH $argv | source
     else
@@ -33,3 +33,6 @@ deactivate
 "#;
 
 pub static INIT_COMMAND: &str = "status --is-interactive; and source (rsvenv init |psub)";
+
+// Passed to fish -C, which runs it after config.fish
+pub static SUBSHELL_TEMPLATE: &str = "source {activate_path}";
@@ -1,9 +1,12 @@
 mod bash;
 mod fish;
+pub mod starship;
 mod zsh;
 use serde::Serialize;
 use simplelog::debug;
+use std::fs;
 use std::path::{Path, PathBuf};
+use std::process;
 
 use std::os::unix::process::parent_id;
 use sysinfo::{Pid, System};
@@ -24,6 +27,13 @@ struct ActivateTemplateContext {
     current_directory: String,
 }
 
+#[derive(Serialize)]
+struct SubshellTemplateContext {
+    activate_path: String,
+    config_dir: String,
+    rc_dir: String,
+}
+
 #[derive(Serialize)]
 struct DeactivateTemplateContext {
     forced: bool,
@@ -52,6 +62,14 @@ impl SupportedShell {
         }
     }
 
+    pub fn program(&self) -> &str {
+        match self {
+            SupportedShell::Zsh => "zsh",
+            SupportedShell::Bash => "bash",
+            SupportedShell::Fish => "fish",
+        }
+    }
+
     pub fn get_hook(&self) -> &str {
         match &self {
             SupportedShell::Zsh => zsh::HOOK,
@@ -110,6 +128,59 @@ impl SupportedShell {
             .context("Unable to render activation template")
     }
 
+    // Makes the command start an interactive shell which sources the activation script after
+    // the user's configuration, so the prompt and deactivate are set up as usual. Generated
+    // configuration files are written to rc_dir
+    pub fn configure_subshell(
+        &self,
+        command: &mut process::Command,
+        venv_root: &Path,
+        rc_dir: &Path,
+    ) -> Result<()> {
+        let config_dir = std::env::var("ZDOTDIR")
+            .or_else(|_| std::env::var("HOME"))
+            .context("Unable to read HOME")?;
+        let context = SubshellTemplateContext {
+            activate_path: format!("{}", &self.get_activate_path(venv_root).display()),
+            config_dir,
+            rc_dir: format!("{}", rc_dir.display()),
+        };
+        let mut tt = TinyTemplate::new();
+        let render = |tt: &mut TinyTemplate, name, template| {
+            tt.add_template(name, template)
+                .context("Unable to add subshell template")?;
+            tt.render(name, &context)
+                .context("Unable to render subshell template")
+        };
+        match self {
+            SupportedShell::Bash => {
+                let rc_file = rc_dir.join("bashrc");
+                fs::write(&rc_file, render(&mut tt, "rc", bash::SUBSHELL_TEMPLATE)?)
+                    .context("Unable to write bash configuration")?;
+                command.arg("--rcfile").arg(rc_file);
+            }
+            SupportedShell::Zsh => {
+                fs::write(
+                    rc_dir.join(".zshenv"),
+                    render(&mut tt, "env", zsh::SUBSHELL_ENV_TEMPLATE)?,
+                )
+                .context("Unable to write zsh configuration")?;
+                fs::write(
+                    rc_dir.join(".zshrc"),
+                    render(&mut tt, "rc", zsh::SUBSHELL_TEMPLATE)?,
+                )
+                .context("Unable to write zsh configuration")?;
+                command.env("ZDOTDIR", rc_dir);
+            }
+            SupportedShell::Fish => {
+                command
+                    .arg("-C")
+                    .arg(render(&mut tt, "rc", fish::SUBSHELL_TEMPLATE)?);
+            }
+        }
+        Ok(())
+    }
+
     pub fn render_deactivate(&self, forced: bool) -> Result<String> {
         let context = DeactivateTemplateContext { forced };
         let mut tt = TinyTemplate::new();
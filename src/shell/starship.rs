@@ -0,0 +1,9 @@
+pub const MODULE: &str = r#"
+# Add to ~/.config/starship.toml
+[custom.rsvenv]
+command = "rsvenv prompt"
+when = """ test -n "$VIRTUAL_ENV" """
+format = "via [$output]($style) "
+style = "yellow bold"
+shell = ["sh"]
+"#;
@@ -15,19 +15,22 @@ rsvenv () {
     SUBPARAMS=("${ALL_PARAMS[@]:1}")
     case $COMMAND in
     "activate")
-        eval "$($RS_VENV_PATH activate $SUBPARAMS)"
+        eval "$($RS_VENV_PATH activate "${SUBPARAMS[@]}")"
         ;;
     "deactivate")
-        eval "$($RS_VENV_PATH deactivate $SUBPARAMS)"
+        eval "$($RS_VENV_PATH deactivate "${SUBPARAMS[@]}")"
+        ;;
+    "create")
+        eval "$($RS_VENV_PATH create "${SUBPARAMS[@]}")"
         ;;
     "delete")
-        eval "$($RS_VENV_PATH delete $SUBPARAMS)"
+        eval "$($RS_VENV_PATH delete "${SUBPARAMS[@]}")"
         ;;
     "use")
-        eval "$($RS_VENV_PATH use $SUBPARAMS)"
+        eval "$($RS_VENV_PATH use "${SUBPARAMS[@]}")"
         ;;
     *)
-        $RS_VENV_PATH $ALL_PARAMS
+        $RS_VENV_PATH "${ALL_PARAMS[@]}"
         ;;
     esac
 }
@@ -38,3 +41,18 @@ if [[ -z $precmd_functions[(r)_rs_venv_virtualenv_hook] ]]; then
   precmd_functions=(_rs_venv_virtualenv_hook $precmd_functions);
 fi
 "#;
+
+// ZDOTDIR points to the generated files, which source the user's ones and restore it
+pub static SUBSHELL_ENV_TEMPLATE: &str = r#"
+ZDOTDIR={config_dir}
+[ -f $ZDOTDIR/.zshenv ] && source $ZDOTDIR/.zshenv
+_rsvenv_zdotdir=$ZDOTDIR
+ZDOTDIR={rc_dir}
+"#;
+
+pub static SUBSHELL_TEMPLATE: &str = r#"
+ZDOTDIR=$_rsvenv_zdotdir
+unset _rsvenv_zdotdir
+[ -f $ZDOTDIR/.zshrc ] && source $ZDOTDIR/.zshrc
+source {activate_path}
+"#;
@@ -1,24 +1,113 @@
+pub mod aliases;
+pub mod archive;
+pub mod distributions;
+pub mod health;
+pub mod inventory;
 pub mod local;
+pub mod locks;
+pub mod projects;
 pub mod pyenv;
-mod python;
+pub mod python;
+pub mod pyvenv_cfg;
+pub mod relocate;
+pub mod repair;
 pub mod rsenv;
+pub mod snapshots;
+pub mod spec;
 pub mod traits;
-mod utils;
+pub mod trash;
+pub mod usage;
+pub mod utils;
 
 use anyhow::Context;
 use anyhow::Result;
 use simplelog::info;
 
 use std::collections::HashSet;
+use std::fs;
 use std::io;
 use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process;
 
 use self::local::Local;
+use self::locks::Lock;
 use self::pyenv::Pyenv;
 use self::rsenv::Rsenv;
 use self::traits::VirtualEnvCompatible;
 use crate::shell::SupportedShell;
-use crate::virtualenv::utils::{get_current_dir, is_virtualenv};
+use crate::virtualenv::utils::{get_current_dir, is_virtualenv, mark_used};
+
+pub const BACKENDS: [&dyn VirtualEnvCompatible; 3] = [&Rsenv, &Pyenv, &Local];
+
+pub fn find_backend(name: &String) -> Result<&'static dyn VirtualEnvCompatible> {
+    BACKENDS
+        .into_iter()
+        .find(|backend| backend.contains(name))
+        .with_context(|| format!("{name} is not a known virtual environment"))
+}
+
+// Path of the named venv of any backend, or of the venv the current directory is bound to
+pub fn resolve_venv(name: Option<&String>) -> Result<PathBuf> {
+    let path = match name {
+        Some(name) => find_backend(name)?.path(Some(name))?,
+        None => detect_backend()
+            .context("No virtual environment is bound to the current directory")?
+            .path(None)?,
+    };
+    is_virtualenv(&path)?;
+    Ok(path)
+}
+
+// PATH without the bin directory of the venv active in the current shell
+fn inactive_path() -> Vec<PathBuf> {
+    let active = std::env::var_os("VIRTUAL_ENV").map(|venv| PathBuf::from(venv).join("bin"));
+    std::env::split_paths(&std::env::var_os("PATH").unwrap_or_default())
+        .filter(|dir| Some(dir) != active.as_ref())
+        .collect()
+}
+
+// Command running outside of any venv, even when one is active in the current shell
+pub fn inactive_command(program: &str) -> process::Command {
+    let mut command = process::Command::new(program);
+    command
+        .env(
+            "PATH",
+            std::env::join_paths(inactive_path()).unwrap_or_default(),
+        )
+        .env_remove("VIRTUAL_ENV")
+        .env_remove("VIRTUAL_ENV_PROMPT")
+        .env_remove("PYTHONHOME");
+    command
+}
+
+// Command running in the venv environment, as if it was activated
+pub fn venv_command(venv: &Path, program: &str) -> process::Command {
+    let mut path = vec![venv.join("bin")];
+    path.extend(inactive_path());
+    let mut command = inactive_command(program);
+    command
+        .env("VIRTUAL_ENV", venv)
+        .env("PATH", std::env::join_paths(path).unwrap_or_default());
+    command
+}
+
+// Backend and name of the venv stored at the path. Runs on every prompt, so only the path
+// itself is checked instead of listing the venvs of each backend
+pub fn find_by_path(path: &Path) -> Option<(&'static dyn VirtualEnvCompatible, String)> {
+    is_virtualenv(path).ok()?;
+    BACKENDS.into_iter().find_map(|backend| {
+        let root = backend.root_dir().ok()?;
+        let root = fs::canonicalize(&root).unwrap_or(root);
+        let name = path.strip_prefix(root).ok()?.to_str()?.to_string();
+        backend.has_name_shape(&name).then_some((backend, name))
+    })
+}
+
+// Backend of the venv the current directory is bound to, in order of precedence
+pub fn detect_backend() -> Option<&'static dyn VirtualEnvCompatible> {
+    BACKENDS.into_iter().find(|backend| backend.relevant())
+}
 
 pub struct VirtualEnvironment<'a> {
     // Venv path
@@ -39,25 +128,19 @@ impl<'a> VirtualEnvironment<'a> {
     }
 
     pub fn detect() -> Option<Self> {
-        if Rsenv.relevant() {
-            return Self::new(&Rsenv).ok();
-        }
-
-        if Pyenv.relevant() {
-            return Self::new(&Pyenv).ok();
-        }
-
-        if Local.relevant() {
-            return Self::new(&Local).ok();
-        }
-
-        None
+        Self::new(detect_backend()?).ok()
     }
 
     pub fn activate(&self, venv_name: Option<&String>) -> Result<()> {
         let path = self.kind.path(venv_name)?;
         is_virtualenv(&path)?;
+        // Venvs of the store must not be activated while another process replaces or deletes them
+        let _lock = match path.strip_prefix(Rsenv.root_dir()?) {
+            Ok(name) => Some(Lock::venv_shared(&name.to_string_lossy())?),
+            Err(_) => None,
+        };
         info!("Activating {path:?}");
+        mark_used(&path);
         let command = self.shell.render_activate(path, get_current_dir()?)?;
         io::stdout()
             .write_all(command.as_bytes())
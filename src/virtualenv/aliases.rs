@@ -0,0 +1,123 @@
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use anyhow::{bail, Context, Result};
+use simplelog::info;
+
+use super::utils::get_rsvenv_home;
+
+// Aliases of the rsvenv store venvs, stored as `alias=name` lines in <rsvenv home>/aliases
+#[derive(Debug)]
+pub struct Aliases;
+
+impl Aliases {
+    fn file() -> Result<PathBuf> {
+        Ok(get_rsvenv_home()?.join("aliases"))
+    }
+
+    fn parse(content: &str) -> BTreeMap<String, String> {
+        content
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(alias, name)| (alias.trim().to_string(), name.trim().to_string()))
+            .collect()
+    }
+
+    pub fn load() -> BTreeMap<String, String> {
+        Aliases::file()
+            .and_then(|file| Ok(fs::read_to_string(file)?))
+            .map(|content| Aliases::parse(&content))
+            .unwrap_or_default()
+    }
+
+    fn save(aliases: &BTreeMap<String, String>) -> Result<()> {
+        let file = Aliases::file()?;
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent).context("Unable to create rsvenv directory")?;
+        }
+        let content: String = aliases
+            .iter()
+            .map(|(alias, name)| format!("{alias}={name}\n"))
+            .collect();
+        fs::write(file, content).context("Unable to save aliases")
+    }
+
+    pub fn validate_alias(alias: &str) -> Result<()> {
+        if alias.is_empty()
+            || !alias
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '.' || c == '-')
+        {
+            bail!("alias {alias} is invalid");
+        }
+        Ok(())
+    }
+
+    pub fn resolve(name: &str) -> String {
+        Aliases::load()
+            .remove(name)
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    pub fn pointing_to(name: &str) -> Vec<String> {
+        Aliases::load()
+            .into_iter()
+            .filter(|(_, target)| target == name)
+            .map(|(alias, _)| alias)
+            .collect()
+    }
+
+    pub fn set(alias: &str, name: &str) -> Result<()> {
+        Aliases::validate_alias(alias)?;
+        let mut aliases = Aliases::load();
+        aliases.insert(alias.to_string(), name.to_string());
+        Aliases::save(&aliases)?;
+        info!("Alias {alias} points to {name}");
+        Ok(())
+    }
+
+    pub fn remove(alias: &str) -> Result<()> {
+        let mut aliases = Aliases::load();
+        if aliases.remove(alias).is_none() {
+            bail!("Alias {alias} doesn't exist");
+        }
+        Aliases::save(&aliases)?;
+        info!("Removed alias {alias}");
+        Ok(())
+    }
+
+    // Points aliases of the old venv name to the new one
+    pub fn repoint(old: &str, new: &str) -> Result<()> {
+        let mut aliases = Aliases::load();
+        let mut changed = false;
+        for (alias, target) in aliases.iter_mut().filter(|(_, target)| *target == old) {
+            *target = new.to_string();
+            changed = true;
+            info!("Alias {alias} points to {new}");
+        }
+        if changed {
+            Aliases::save(&aliases)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let aliases = Aliases::parse("proj=3.11.7/myproject\nbroken line\nlint = 3.12.1/lint\n");
+        assert_eq!(aliases.len(), 2);
+        assert_eq!(aliases["proj"], "3.11.7/myproject");
+        assert_eq!(aliases["lint"], "3.12.1/lint");
+    }
+
+    #[test]
+    fn test_validate_alias() {
+        assert!(Aliases::validate_alias("proj-1.0_x").is_ok());
+        assert!(Aliases::validate_alias("3.11.7/proj").is_err());
+        assert!(Aliases::validate_alias("a=b").is_err());
+        assert!(Aliases::validate_alias("").is_err());
+    }
+}
@@ -0,0 +1,192 @@
+use std::{
+    fs::{self, File},
+    io::{BufReader, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, Context, Result};
+use serde_derive::{Deserialize, Serialize};
+use simplelog::info;
+
+use super::{
+    distributions::installed,
+    health::check,
+    python::{minor_version, PythonInterpreter},
+    relocate::{recorded_path, relocate},
+    repair::repair,
+    rsenv::Rsenv,
+    utils::get_rsvenv_home,
+};
+
+const MANIFEST: &str = "rsvenv-manifest.json";
+const VENV_DIR: &str = "venv";
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub name: String,
+    // Absolute path of the venv on the exporting machine, used for relocation
+    pub path: String,
+    pub python_version: String,
+    pub implementation: String,
+    pub packages: Vec<String>,
+}
+
+impl Manifest {
+    pub fn new(name: &str, venv: &Path) -> Result<Self> {
+        let interpreter =
+            PythonInterpreter::new(&venv.join("bin").join("python").display().to_string())?;
+        Ok(Manifest {
+            name: name.to_string(),
+            // Files of the venv use the path it was created at, not the canonical one
+            path: recorded_path(venv)
+                .unwrap_or(venv.to_path_buf())
+                .display()
+                .to_string(),
+            implementation: interpreter.implementation()?,
+            python_version: interpreter.version,
+            packages: installed(venv)?.iter().map(|d| d.requirement()).collect(),
+        })
+    }
+}
+
+// Archive contains the manifest and the venv in the venv/ directory.
+// It is compressed with zstd when the file name ends with .zst
+pub fn export(name: &str, venv: &Path, output: &Path) -> Result<()> {
+    let manifest = serde_json::to_vec_pretty(&Manifest::new(name, venv)?)
+        .context("Unable to serialize manifest")?;
+    let file =
+        File::create(output).with_context(|| format!("Unable to create {}", output.display()))?;
+    let writer: Box<dyn Write> = if output.extension().is_some_and(|e| e == "zst") {
+        Box::new(
+            zstd::Encoder::new(file, 0)
+                .context("Unable to initialize zstd")?
+                .auto_finish(),
+        )
+    } else {
+        Box::new(file)
+    };
+    let mut builder = tar::Builder::new(writer);
+    builder.follow_symlinks(false);
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+    );
+    header.set_cksum();
+    builder
+        .append_data(&mut header, MANIFEST, manifest.as_slice())
+        .context("Unable to write manifest")?;
+    builder
+        .append_dir_all(VENV_DIR, venv)
+        .context("Unable to write virtual env to the archive")?;
+    builder
+        .into_inner()
+        .context("Unable to finish the archive")?
+        .flush()
+        .context("Unable to write the archive")?;
+    info!("Exported {name} to {}", output.display());
+    Ok(())
+}
+
+fn unpack(archive: &Path, destination: &Path) -> Result<()> {
+    let mut file =
+        File::open(archive).with_context(|| format!("Unable to open {}", archive.display()))?;
+    let mut magic = [0u8; 4];
+    let compressed = file.read_exact(&mut magic).is_ok() && magic == ZSTD_MAGIC;
+    file.seek(SeekFrom::Start(0))
+        .context("Unable to read the archive")?;
+    let reader: Box<dyn Read> = if compressed {
+        Box::new(zstd::Decoder::new(file).context("Unable to initialize zstd")?)
+    } else {
+        Box::new(BufReader::new(file))
+    };
+    let mut archive = tar::Archive::new(reader);
+    archive.set_preserve_permissions(true);
+    archive
+        .unpack(destination)
+        .context("Unable to unpack the archive")
+}
+
+fn import_unpacked(dir: &Path, name: Option<&String>) -> Result<String> {
+    let manifest: Manifest = serde_json::from_slice(
+        &fs::read(dir.join(MANIFEST)).context("Archive doesn't contain a manifest")?,
+    )
+    .context("Unable to parse manifest")?;
+    let name = name.unwrap_or(&manifest.name).clone();
+    Rsenv::validate_name(&name)?;
+    if !name.contains('/') {
+        bail!("{name} doesn't start with the python version, use --name <version>/{name}");
+    }
+    let (path, _lock) = Rsenv.reserve(&name)?;
+    if let Err(e) = fs::rename(dir.join(VENV_DIR), &path) {
+        fs::remove_dir_all(&path).unwrap_or_default();
+        return Err(e).context("Unable to move virtual env to the store");
+    }
+    relocate(&path, &PathBuf::from(&manifest.path), &path)?;
+
+    // The interpreter the venv actually runs must be the same python as in the manifest
+    let matches = |interpreter: &PythonInterpreter| {
+        interpreter.minor_version() == minor_version(&manifest.python_version)
+            && interpreter
+                .implementation()
+                .is_ok_and(|implementation| implementation == manifest.implementation)
+    };
+    let python = path.join("bin").join("python");
+    let current = match check(&path) {
+        Some(problem) => {
+            info!("{name} needs an interpreter: {problem}");
+            None
+        }
+        None => PythonInterpreter::new(&python.display().to_string()).ok(),
+    };
+    if !current.as_ref().is_some_and(matches) {
+        if let Some(current) = &current {
+            info!(
+                "{name} uses python {}, but the archive requires {} {}",
+                current.version, manifest.implementation, manifest.python_version
+            );
+        }
+        match repair(&path, Some(&manifest.python_version)).or_else(|_| repair(&path, None)) {
+            Ok(interpreter) if matches(&interpreter) => {}
+            result => {
+                fs::remove_dir_all(&path).unwrap_or_default();
+                let reason = match result {
+                    Ok(interpreter) => format!(
+                        "{} is {} {}",
+                        interpreter.interpreter,
+                        interpreter.implementation().unwrap_or_default(),
+                        interpreter.version
+                    ),
+                    Err(e) => e.to_string(),
+                };
+                bail!(
+                    "No interpreter matching {} {} found: {reason}",
+                    manifest.implementation,
+                    manifest.python_version
+                );
+            }
+        }
+    }
+    Ok(name)
+}
+
+pub fn import(archive: &Path, name: Option<&String>) -> Result<String> {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let tmp = get_rsvenv_home()?.join(format!("import-{millis}"));
+    fs::create_dir_all(&tmp).context("Unable to create temporary directory")?;
+    let result = unpack(archive, &tmp).and_then(|_| import_unpacked(&tmp, name));
+    fs::remove_dir_all(&tmp).unwrap_or_default();
+    let name = result?;
+    info!("Imported {} as {name}", archive.display());
+    Ok(name)
+}
@@ -0,0 +1,91 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use itertools::Itertools;
+
+// Packages installed by venv itself, skipped the same way as `pip freeze` does
+const BOOTSTRAP_PACKAGES: [&str; 4] = ["pip", "setuptools", "wheel", "distribute"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Distribution {
+    pub name: String,
+    pub version: String,
+}
+
+impl Distribution {
+    pub fn requirement(&self) -> String {
+        format!("{}=={}", self.name, self.version)
+    }
+
+    pub fn is_bootstrap(&self) -> bool {
+        BOOTSTRAP_PACKAGES.contains(&self.name.to_lowercase().as_str())
+    }
+
+    fn parse_metadata(content: &str) -> Option<Self> {
+        let mut name = None;
+        let mut version = None;
+        // Headers end at the first empty line, the description follows
+        for line in content.lines().take_while(|line| !line.is_empty()) {
+            if let Some(value) = line.strip_prefix("Name:") {
+                name = Some(value.trim().to_string());
+            } else if let Some(value) = line.strip_prefix("Version:") {
+                version = Some(value.trim().to_string());
+            }
+        }
+        Some(Distribution {
+            name: name?,
+            version: version?,
+        })
+    }
+}
+
+// Distributions installed into the venv site-packages, sorted by name
+pub fn installed(venv: &Path) -> Result<Vec<Distribution>> {
+    let pattern = format!("{}/lib/python*/site-packages/*.dist-info", venv.display());
+    Ok(glob::glob(&pattern)
+        .context("Unable to list site-packages")?
+        .flatten()
+        .filter_map(|dist_info| fs::read_to_string(dist_info.join("METADATA")).ok())
+        .filter_map(|metadata| Distribution::parse_metadata(&metadata))
+        .sorted_by_key(|d| d.name.to_lowercase())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_metadata() {
+        let metadata =
+            "Metadata-Version: 2.1\nName: requests\nVersion: 2.31.0\n\nName: not a header\n";
+        assert_eq!(
+            Distribution::parse_metadata(metadata),
+            Some(Distribution {
+                name: "requests".to_string(),
+                version: "2.31.0".to_string()
+            })
+        );
+        assert_eq!(Distribution::parse_metadata("Name: broken\n"), None);
+    }
+
+    #[test]
+    fn test_installed() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let site_packages = dir.as_ref().join("lib/python3.11/site-packages");
+        for (name, version) in [("pip", "23.2"), ("Django", "5.0"), ("attrs", "23.1.0")] {
+            let dist_info = site_packages.join(format!("{name}-{version}.dist-info"));
+            fs::create_dir_all(&dist_info).unwrap();
+            fs::write(
+                dist_info.join("METADATA"),
+                format!("Name: {name}\nVersion: {version}\n"),
+            )
+            .unwrap();
+        }
+        let result = installed(dir.as_ref()).unwrap();
+        let names: Vec<_> = result.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["attrs", "Django", "pip"]);
+        assert!(result[2].is_bootstrap());
+        assert_eq!(result[1].requirement(), "Django==5.0");
+    }
+}
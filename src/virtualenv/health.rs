@@ -0,0 +1,180 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use super::{pyvenv_cfg::PyvenvCfg, utils::is_virtualenv};
+
+#[derive(Debug, PartialEq)]
+pub enum Problem {
+    // Creation was interrupted: some of the required files are missing
+    HalfCreated(&'static str),
+    // bin/python points to an interpreter that doesn't exist anymore
+    Broken(PathBuf),
+    // Base interpreter directory from pyvenv.cfg doesn't exist anymore
+    Orphaned(PathBuf),
+    // Directory in the store that contains no virtual environments
+    Empty,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::HalfCreated(missing) => write!(f, "half-created: {missing} is missing"),
+            Problem::Broken(target) => {
+                write!(f, "broken: interpreter {} is missing", target.display())
+            }
+            Problem::Orphaned(home) => write!(f, "orphaned: home {} is missing", home.display()),
+            Problem::Empty => write!(f, "empty directory"),
+        }
+    }
+}
+
+impl Problem {
+    pub fn repairable(&self) -> bool {
+        matches!(self, Problem::Broken(_) | Problem::Orphaned(_))
+    }
+}
+
+fn looks_like_venv(path: &Path) -> bool {
+    path.join("pyvenv.cfg").exists() || path.join("bin").is_dir()
+}
+
+pub fn check(path: &Path) -> Option<Problem> {
+    let cfg = match PyvenvCfg::read(path) {
+        Ok(cfg) => cfg,
+        Err(_) => return Some(Problem::HalfCreated("pyvenv.cfg")),
+    };
+    if is_virtualenv(path).is_err() {
+        return Some(Problem::HalfCreated("bin/activate"));
+    }
+    let python = path.join("bin").join("python");
+    if fs::symlink_metadata(&python).is_err() {
+        return Some(Problem::HalfCreated("bin/python"));
+    }
+    if fs::metadata(&python).is_err() {
+        let target = fs::read_link(&python).unwrap_or(python);
+        return Some(Problem::Broken(path.join("bin").join(target)));
+    }
+    match cfg.home() {
+        Some(home) if !home.is_dir() => Some(Problem::Orphaned(home)),
+        _ => None,
+    }
+}
+
+// Whether the problem found by scan is still there, e.g. right before removing the directory
+pub fn persists(path: &Path, problem: &Problem) -> bool {
+    match problem {
+        Problem::Empty => fs::read_dir(path).is_ok_and(|mut entries| entries.next().is_none()),
+        _ => check(path).is_some(),
+    }
+}
+
+// Checks all the directories of the store that are venvs or version prefixes containing venvs
+pub fn scan(root: &Path) -> Vec<(String, PathBuf, Problem)> {
+    let mut result = vec![];
+    let Ok(entries) = fs::read_dir(root) else {
+        return result;
+    };
+    for dir in entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()) {
+        let mut candidates = vec![];
+        if looks_like_venv(&dir) {
+            candidates.push(dir.clone());
+        } else if let Ok(children) = fs::read_dir(&dir) {
+            candidates.extend(children.flatten().map(|e| e.path()).filter(|p| p.is_dir()));
+            if candidates.is_empty() {
+                result.push((name(root, &dir), dir.clone(), Problem::Empty));
+            }
+        }
+        for candidate in candidates {
+            if let Some(problem) = check(&candidate) {
+                result.push((name(root, &candidate), candidate, problem));
+            }
+        }
+    }
+    result.sort_by(|a, b| a.0.cmp(&b.0));
+    result
+}
+
+fn name(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    fn make_venv(path: &Path, home: &Path) {
+        fs::create_dir_all(path.join("bin")).unwrap();
+        fs::File::create(path.join("bin").join("activate")).unwrap();
+        fs::write(
+            path.join("pyvenv.cfg"),
+            format!("home = {}\nversion = 3.11.7\n", home.display()),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_check_ok() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let interpreter = dir.as_ref().join("python3");
+        fs::File::create(&interpreter).unwrap();
+        let venv = dir.as_ref().join("venv");
+        make_venv(&venv, dir.as_ref());
+        symlink(&interpreter, venv.join("bin").join("python")).unwrap();
+        assert_eq!(check(&venv), None);
+    }
+
+    #[test]
+    fn test_check_broken() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let interpreter = dir.as_ref().join("python3");
+        let venv = dir.as_ref().join("venv");
+        make_venv(&venv, dir.as_ref());
+        symlink(&interpreter, venv.join("bin").join("python")).unwrap();
+        assert_eq!(check(&venv), Some(Problem::Broken(interpreter)));
+    }
+
+    #[test]
+    fn test_check_half_created() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let venv = dir.as_ref().join("venv");
+        fs::create_dir_all(venv.join("bin")).unwrap();
+        assert_eq!(check(&venv), Some(Problem::HalfCreated("pyvenv.cfg")));
+    }
+
+    #[test]
+    fn test_scan() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let home = dir.as_ref().join("missing");
+        let venv = dir.as_ref().join("3.11.7").join("venv");
+        make_venv(&venv, &home);
+        fs::File::create(venv.join("bin").join("python")).unwrap();
+        fs::create_dir(dir.as_ref().join("3.12.1")).unwrap();
+        let result = scan(dir.as_ref());
+        assert_eq!(
+            result,
+            vec![
+                (
+                    "3.11.7/venv".to_string(),
+                    venv.clone(),
+                    Problem::Orphaned(home)
+                ),
+                (
+                    "3.12.1".to_string(),
+                    dir.as_ref().join("3.12.1"),
+                    Problem::Empty
+                ),
+            ]
+        );
+        assert!(persists(&venv, &result[0].2));
+        assert!(persists(&result[1].1, &Problem::Empty));
+        // A venv is being created in the empty directory
+        fs::create_dir(dir.as_ref().join("3.12.1").join("new")).unwrap();
+        assert!(!persists(&result[1].1, &Problem::Empty));
+    }
+}
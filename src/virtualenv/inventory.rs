@@ -0,0 +1,80 @@
+use std::{fs, path::PathBuf, time::SystemTime};
+
+use itertools::Itertools;
+use serde::Serializer;
+use serde_derive::Serialize;
+
+use super::{
+    detect_backend,
+    pyvenv_cfg::PyvenvCfg,
+    traits::VirtualEnvCompatible,
+    utils::{dir_size, is_active, last_used},
+};
+
+pub fn serialize_time<S: Serializer>(
+    time: &Option<SystemTime>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match time {
+        Some(time) => serializer.collect_str(&humantime::format_rfc3339_seconds(*time)),
+        None => serializer.serialize_none(),
+    }
+}
+
+// Description of a venv known to one of the backends
+#[derive(Debug, Serialize)]
+pub struct VenvEntry {
+    pub backend: &'static str,
+    pub name: String,
+    pub path: PathBuf,
+    pub python: Option<String>,
+    pub size: Option<u64>,
+    #[serde(serialize_with = "serialize_time")]
+    pub last_used: Option<SystemTime>,
+    pub active: bool,
+    // Whether the current directory is bound to the venv
+    pub bound: bool,
+}
+
+impl VenvEntry {
+    // Size is optional as it requires walking the whole venv
+    pub fn new(backend: &dyn VirtualEnvCompatible, name: &str, with_size: bool) -> Option<Self> {
+        let path = backend.root_dir().ok()?.join(name);
+        let path = fs::canonicalize(&path).unwrap_or(path);
+        let python = PyvenvCfg::read(&path)
+            .ok()
+            .and_then(|cfg| cfg.version().map(|version| version.to_string()));
+        Some(VenvEntry {
+            backend: backend.backend(),
+            name: name.to_string(),
+            python,
+            size: with_size.then(|| dir_size(&path)),
+            last_used: last_used(&path),
+            active: is_active(&path),
+            bound: false,
+            path,
+        })
+    }
+}
+
+// Venvs of the backends sorted by backend (in the given order) and name
+pub fn collect(backends: &[&dyn VirtualEnvCompatible], with_size: bool) -> Vec<VenvEntry> {
+    let bound = detect_backend()
+        .and_then(|backend| backend.path(None).ok())
+        .and_then(|path| fs::canonicalize(path).ok());
+    let mut entries = backends
+        .iter()
+        .flat_map(|backend| {
+            backend
+                .list()
+                .into_iter()
+                .sorted()
+                .filter_map(|name| VenvEntry::new(*backend, &name, with_size))
+                .collect_vec()
+        })
+        .collect_vec();
+    for entry in entries.iter_mut() {
+        entry.bound = bound.as_ref() == Some(&entry.path);
+    }
+    entries
+}
@@ -1,30 +1,62 @@
-use anyhow::{bail, Result};
-use std::path::PathBuf;
+use anyhow::{bail, Context, Result};
+use simplelog::info;
+use std::{collections::HashSet, fs, path::PathBuf};
 
 use super::{
     traits::VirtualEnvCompatible,
     utils::{get_current_dir, is_virtualenv},
 };
 
+const LOCAL_VENV_NAMES: [&str; 4] = ["venv", ".venv", "virtualenv", ".virtualenv"];
+
 #[derive(Debug)]
 pub struct Local;
 
 impl VirtualEnvCompatible for Local {
+    fn backend(&self) -> &'static str {
+        "local"
+    }
+
     fn root_dir(&self) -> Result<PathBuf> {
         get_current_dir()
     }
 
+    fn has_name_shape(&self, name: &str) -> bool {
+        LOCAL_VENV_NAMES.contains(&name)
+    }
+
+    fn list(&self) -> HashSet<String> {
+        let Ok(current_path) = self.root_dir() else {
+            return HashSet::new();
+        };
+        LOCAL_VENV_NAMES
+            .iter()
+            .filter(|name| is_virtualenv(&current_path.join(name)).is_ok())
+            .map(|name| name.to_string())
+            .collect()
+    }
+
     fn relevant(&self) -> bool {
         self.venv_name().is_ok()
     }
 
     fn venv_name(&self) -> Result<String> {
         let current_path = self.root_dir()?;
-        for local_venv_path in ["venv", ".venv", "virtualenv", ".virtualenv"] {
+        for local_venv_path in LOCAL_VENV_NAMES {
             if is_virtualenv(&current_path.join(local_venv_path)).is_ok() {
                 return Ok(local_venv_path.to_string());
             }
         }
         bail!("No local venv in {}", current_path.as_path().display());
     }
+
+    fn delete(&self, name: &String, _permanent: bool) -> Result<()> {
+        if !self.list().contains(name) {
+            bail!("Cannot delete virtual environment: {name} is not a local venv");
+        }
+        let path = self.root_dir()?.join(name);
+        fs::remove_dir_all(&path).context("Unable to delete virtual env")?;
+        info!("Deleted local venv {}", path.display());
+        Ok(())
+    }
 }
@@ -0,0 +1,189 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File, TryLockError},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use lazy_static::lazy_static;
+use simplelog::debug;
+
+use super::utils::get_rsvenv_home;
+use crate::configuration::SETTINGS;
+
+lazy_static! {
+    // Lock files held by this process with the mode (shared or not) and the number of holders,
+    // so nested operations (e.g. upgrade deleting the venv it locked) don't wait for themselves
+    static ref HELD: Mutex<HashMap<PathBuf, (File, bool, usize)>> = Mutex::new(HashMap::new());
+}
+
+const RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+// Advisory lock of the rsvenv store or a single venv, released on drop
+#[derive(Debug)]
+pub struct Lock {
+    path: PathBuf,
+}
+
+impl Lock {
+    fn timeout() -> Result<Duration> {
+        let settings = SETTINGS
+            .read()
+            .map_err(|e| anyhow!("Unable to read lock timeout from SETTINGS: {e:?}"))?;
+        humantime::parse_duration(&settings.lock_timeout)
+            .with_context(|| format!("Invalid lock timeout {}", settings.lock_timeout))
+    }
+
+    fn dir() -> Result<PathBuf> {
+        Ok(get_rsvenv_home()?.join("locks"))
+    }
+
+    // Exclusive lock of the store, taken by operations adding or removing venvs
+    pub fn store() -> Result<Lock> {
+        Lock::acquire(&Lock::dir()?.join("store.lock"), false, "rsvenv store")
+    }
+
+    // Shared lock of the store, taken by operations reading it
+    pub fn store_shared() -> Result<Lock> {
+        Lock::acquire(&Lock::dir()?.join("store.lock"), true, "rsvenv store")
+    }
+
+    fn venv_path(name: &str) -> Result<PathBuf> {
+        Ok(Lock::dir()?.join("venvs").join(format!("{name}.lock")))
+    }
+
+    // Exclusive lock of a single venv of the store
+    pub fn venv(name: &str) -> Result<Lock> {
+        Lock::acquire(
+            &Lock::venv_path(name)?,
+            false,
+            &format!("Virtual environment {name}"),
+        )
+    }
+
+    // Shared lock of a single venv, taken by operations reading it (e.g. activation)
+    pub fn venv_shared(name: &str) -> Result<Lock> {
+        Lock::acquire(
+            &Lock::venv_path(name)?,
+            true,
+            &format!("Virtual environment {name}"),
+        )
+    }
+
+    // Exclusive lock of a single venv which doesn't wait, for operations skipping busy venvs
+    pub fn try_venv(name: &str) -> Result<Lock> {
+        Lock::acquire_with_timeout(
+            &Lock::venv_path(name)?,
+            false,
+            &format!("Virtual environment {name}"),
+            Duration::ZERO,
+        )
+    }
+
+    pub fn acquire(path: &Path, shared: bool, what: &str) -> Result<Lock> {
+        Lock::acquire_with_timeout(path, shared, what, Lock::timeout()?)
+    }
+
+    fn acquire_with_timeout(
+        path: &Path,
+        shared: bool,
+        what: &str,
+        timeout: Duration,
+    ) -> Result<Lock> {
+        let mut held = HELD
+            .lock()
+            .map_err(|e| anyhow!("Unable to track locks: {e:?}"))?;
+        if let Some((_, held_shared, holders)) = held.get_mut(path) {
+            // Converting a lock is not atomic, so a shared lock is never made exclusive
+            if *held_shared && !shared {
+                bail!("{what} is locked for reading by this process");
+            }
+            *holders += 1;
+            return Ok(Lock {
+                path: path.to_path_buf(),
+            });
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Unable to create locks directory")?;
+        }
+        let file = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .with_context(|| format!("Unable to open lock file {}", path.display()))?;
+        let started = Instant::now();
+        loop {
+            let result = if shared {
+                file.try_lock_shared()
+            } else {
+                file.try_lock()
+            };
+            match result {
+                Ok(()) => break,
+                Err(TryLockError::WouldBlock) if started.elapsed() < timeout => {
+                    debug!("{what} is busy, waiting");
+                    thread::sleep(RETRY_INTERVAL);
+                }
+                Err(TryLockError::WouldBlock) => {
+                    bail!("{what} is busy: another rsvenv process is using it")
+                }
+                Err(TryLockError::Error(e)) => {
+                    return Err(e).with_context(|| format!("Unable to lock {}", path.display()))
+                }
+            }
+        }
+        held.insert(path.to_path_buf(), (file, shared, 1));
+        Ok(Lock {
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        if let Ok(mut held) = HELD.lock() {
+            if let Some((_, _, holders)) = held.get_mut(&self.path) {
+                *holders -= 1;
+                if *holders == 0 {
+                    // Closing the file releases the lock
+                    held.remove(&self.path);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_locked(path: &Path) -> bool {
+        let file = File::open(path).unwrap();
+        matches!(file.try_lock(), Err(TryLockError::WouldBlock))
+    }
+
+    #[test]
+    fn test_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("locks").join("store.lock");
+        let timeout = Duration::from_millis(10);
+
+        let lock = Lock::acquire_with_timeout(&path, false, "store", timeout).unwrap();
+        assert!(is_locked(&path));
+        let nested = Lock::acquire_with_timeout(&path, false, "store", timeout).unwrap();
+        drop(lock);
+        assert!(is_locked(&path));
+        drop(nested);
+        assert!(!is_locked(&path));
+
+        // A shared lock held by this process is not made exclusive
+        let shared = Lock::acquire_with_timeout(&path, true, "store", timeout).unwrap();
+        assert!(Lock::acquire_with_timeout(&path, false, "store", timeout).is_err());
+        drop(shared);
+        assert!(Lock::acquire_with_timeout(&path, false, "store", timeout).is_ok());
+    }
+}
@@ -0,0 +1,106 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use simplelog::{debug, info};
+
+use super::{
+    aliases::Aliases,
+    utils::{get_current_dir, get_rsvenv_home},
+};
+
+pub const MARKERS: [&str; 2] = [".python-virtualenv", ".python-version"];
+
+// Registry of the directories where `rsvenv use` has written a marker file
+#[derive(Debug)]
+pub struct Projects;
+
+impl Projects {
+    fn registry() -> Result<PathBuf> {
+        Ok(get_rsvenv_home()?.join("projects"))
+    }
+
+    pub fn list() -> Vec<PathBuf> {
+        let Ok(registry) = Projects::registry() else {
+            return vec![];
+        };
+        fs::read_to_string(registry)
+            .unwrap_or_default()
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(PathBuf::from)
+            .collect()
+    }
+
+    pub fn register(dir: &Path) -> Result<()> {
+        let mut projects = Projects::list();
+        if projects.iter().any(|p| p == dir) {
+            return Ok(());
+        }
+        projects.push(dir.to_path_buf());
+        Projects::write(&projects)
+    }
+
+    fn write(projects: &[PathBuf]) -> Result<()> {
+        let registry = Projects::registry()?;
+        if let Some(parent) = registry.parent() {
+            fs::create_dir_all(parent).context("Unable to create rsvenv directory")?;
+        }
+        let content: String = projects
+            .iter()
+            .map(|p| format!("{}\n", p.display()))
+            .collect();
+        fs::write(registry, content).context("Unable to save the list of projects")
+    }
+
+    pub fn marker(dir: &Path) -> Option<(PathBuf, String)> {
+        for marker in MARKERS {
+            let path = dir.join(marker);
+            if let Ok(content) = fs::read_to_string(&path) {
+                return Some((path, content.trim().to_string()));
+            }
+        }
+        None
+    }
+
+    // Points marker files that reference the old venv name to the new one
+    pub fn rebind(old: &str, new: &str) -> Result<Vec<PathBuf>> {
+        let markers = Projects::using(old);
+        for marker in markers.iter() {
+            fs::write(marker, new)
+                .with_context(|| format!("Unable to update {}", marker.display()))?;
+            info!("Updated {}", marker.display());
+        }
+        Ok(markers)
+    }
+
+    // Marker files of the known projects (and of the current directory) that reference the venv
+    pub fn using(name: &str) -> Vec<PathBuf> {
+        let mut dirs = Projects::list();
+        if let Ok(current_dir) = get_current_dir() {
+            if !dirs.contains(&current_dir) {
+                dirs.push(current_dir);
+            }
+        }
+        dirs.iter()
+            .filter_map(|dir| Projects::marker(dir))
+            .filter(|(path, value)| {
+                debug!("{} references {value}", path.display());
+                value == name
+            })
+            .map(|(path, _)| path)
+            .collect()
+    }
+
+    // Marker files referencing the venv by its name or one of its aliases
+    pub fn using_venv(name: &str) -> Vec<PathBuf> {
+        std::iter::once(name.to_string())
+            .chain(Aliases::pointing_to(name))
+            .flat_map(|name| Projects::using(&name))
+            .unique()
+            .collect()
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use std::{
     collections::HashSet,
     fs::{self, File},
@@ -9,6 +9,7 @@ use std::{
 use simplelog::{debug, info};
 
 use super::{
+    projects::Projects,
     traits::VirtualEnvCompatible,
     utils::{get_current_dir, get_venvs_by_glob},
 };
@@ -16,7 +17,44 @@ use super::{
 #[derive(Debug)]
 pub struct Pyenv;
 
+impl Pyenv {
+    // pyenv-virtualenv creates <version>/envs/<name> and a versions/<name> symlink to it
+    fn full_name(&self, name: &String) -> Option<String> {
+        let venvs = self.list();
+        if venvs.contains(name) {
+            return Some(name.clone());
+        }
+        if name.contains('/') {
+            return None;
+        }
+        let root = self.root_dir().ok()?;
+        let target = fs::canonicalize(root.join(name)).ok()?;
+        let full_name = target
+            .strip_prefix(fs::canonicalize(&root).ok()?)
+            .ok()?
+            .to_str()?
+            .to_string();
+        venvs.contains(&full_name).then_some(full_name)
+    }
+
+    fn symlinks_to(&self, path: &Path) -> Vec<PathBuf> {
+        let Ok(entries) = self.root_dir().and_then(|root| Ok(fs::read_dir(root)?)) else {
+            return vec![];
+        };
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|link| link.is_symlink())
+            .filter(|link| fs::canonicalize(link).is_ok_and(|target| target == path))
+            .collect()
+    }
+}
+
 impl VirtualEnvCompatible for Pyenv {
+    fn backend(&self) -> &'static str {
+        "pyenv"
+    }
+
     fn root_dir(&self) -> Result<PathBuf> {
         let root = std::env::var("PYENV_ROOT").unwrap_or("~/.pyenv".to_string());
         let expanded = shellexpand::full(&root)
@@ -32,6 +70,14 @@ impl VirtualEnvCompatible for Pyenv {
         HashSet::new()
     }
 
+    fn has_name_shape(&self, name: &str) -> bool {
+        matches!(name.split('/').collect::<Vec<_>>()[..], [_, "envs", _])
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.full_name(&name.to_string()).is_some()
+    }
+
     fn relevant(&self) -> bool {
         let current_dir = get_current_dir();
         if current_dir.is_err() {
@@ -63,6 +109,22 @@ impl VirtualEnvCompatible for Pyenv {
             .context("Unable to save data .python-version")?;
         info!("Saved changes to .python-version");
         fs::remove_file(".python-virtualenv").unwrap_or_default();
+        Projects::register(&get_current_dir()?)
+    }
+
+    fn delete(&self, name: &String, _permanent: bool) -> Result<()> {
+        let Some(full_name) = self.full_name(name) else {
+            bail!("Cannot delete virtual environment: {name} is not managed by pyenv");
+        };
+        let path = fs::canonicalize(self.root_dir()?.join(&full_name))
+            .context("Unable to resolve virtual env path")?;
+        for link in self.symlinks_to(&path) {
+            fs::remove_file(&link)
+                .with_context(|| format!("Unable to delete symlink {}", link.display()))?;
+            debug!("Deleted symlink {}", link.display());
+        }
+        fs::remove_dir_all(&path).context("Unable to delete virtual env")?;
+        info!("Deleted pyenv venv {full_name}");
         Ok(())
     }
 }
@@ -1,33 +1,159 @@
-use std::{path::PathBuf, process, str::FromStr};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::{self, Stdio},
+    str::FromStr,
+};
 
 use anyhow::{bail, Context, Result};
 use glob::Pattern;
+use regex::Regex;
 use simplelog::info;
 
-pub struct PythonInterpreter<'a> {
+use super::{pyenv::Pyenv, pyvenv_cfg::version_matches, traits::VirtualEnvCompatible};
+
+pub fn parse_version(version: &str) -> Vec<u32> {
+    version
+        .split('.')
+        .map_while(|part| part.parse().ok())
+        .collect()
+}
+
+pub fn minor_version(version: &str) -> String {
+    version.split('.').take(2).collect::<Vec<_>>().join(".")
+}
+
+pub struct PythonInterpreter {
     pub version: String,
-    pub interpreter: &'a String,
+    pub interpreter: String,
 }
 
-impl<'a> PythonInterpreter<'a> {
-    pub fn new(interpreter: &'a String) -> Result<Self> {
+impl PythonInterpreter {
+    pub fn new(interpreter: &str) -> Result<Self> {
         let version = PythonInterpreter::detect_version(interpreter)?;
         Ok(PythonInterpreter {
             version,
-            interpreter,
+            interpreter: interpreter.to_string(),
         })
     }
 
-    pub fn create_venv(&self, path: &PathBuf) -> Result<()> {
+    // Spec is either a python executable or a version prefix (e.g. 3.11 or 3.11.9).
+    // For a version prefix the most recent matching interpreter from pyenv or PATH is used
+    pub fn find(spec: &str) -> Result<Self> {
+        if !Regex::new(r"^\d+(\.\d+)*$").unwrap().is_match(spec) {
+            return PythonInterpreter::new(spec);
+        }
+        let mut candidates: Vec<(Vec<u32>, String)> = vec![];
+        if let Ok(versions) = Pyenv.root_dir() {
+            for python in glob::glob(&format!("{}/*/bin/python", versions.display()))
+                .context("Unable to list pyenv versions")?
+                .flatten()
+            {
+                let Some(version) = python
+                    .parent()
+                    .and_then(|bin| bin.parent())
+                    .and_then(|dir| dir.file_name())
+                    .and_then(|name| name.to_str())
+                else {
+                    continue;
+                };
+                if version_matches(version, spec) {
+                    candidates.push((parse_version(version), python.display().to_string()));
+                }
+            }
+        }
+        let minor_pattern = Regex::new(r"^\d+\.\d+$").unwrap();
+        for dir in std::env::split_paths(&std::env::var_os("PATH").unwrap_or_default()) {
+            for python in glob::glob(&format!("{}/python3.*", dir.display()))
+                .into_iter()
+                .flatten()
+                .flatten()
+            {
+                let Some(minor) = python
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.strip_prefix("python"))
+                    .filter(|minor| minor_pattern.is_match(minor))
+                else {
+                    continue;
+                };
+                if !version_matches(minor, spec) && !version_matches(spec, minor) {
+                    continue;
+                }
+                let python = python.display().to_string();
+                if let Ok(version) = PythonInterpreter::detect_version(&python) {
+                    if version_matches(&version, spec) {
+                        candidates.push((parse_version(&version), python));
+                    }
+                }
+            }
+        }
+        match candidates.into_iter().max_by(|a, b| a.0.cmp(&b.0)) {
+            Some((_, python)) => PythonInterpreter::new(&python),
+            None => bail!("Unable to find python {spec}"),
+        }
+    }
+
+    // Real path of the interpreter, e.g. when it is started through a pyenv shim
+    pub fn executable(&self) -> Result<PathBuf> {
+        let output = process::Command::new(&self.interpreter)
+            .arg("-c")
+            .arg("import sys; print(sys.executable)")
+            .output()
+            .context("Unable to spawn interpreter")?;
+        if output.status.code().unwrap_or(1) != 0 {
+            bail!("Python executable returned {}", output.status);
+        }
+        let executable = String::from_utf8(output.stdout).context("unable to read stdout")?;
+        Ok(PathBuf::from(executable.trim()))
+    }
+
+    pub fn implementation(&self) -> Result<String> {
+        let output = process::Command::new(&self.interpreter)
+            .arg("-c")
+            .arg("import platform; print(platform.python_implementation())")
+            .output()
+            .context("Unable to spawn interpreter")?;
+        if output.status.code().unwrap_or(1) != 0 {
+            bail!("Python executable returned {}", output.status);
+        }
+        let implementation = String::from_utf8(output.stdout).context("unable to read stdout")?;
+        Ok(implementation.trim().to_string())
+    }
+
+    pub fn minor_version(&self) -> String {
+        minor_version(&self.version)
+    }
+
+    // Interpreter from the base python installation directory (`home` in pyvenv.cfg)
+    pub fn from_home(home: &Path, version: Option<&str>) -> Result<Self> {
+        let mut candidates = vec![];
+        if let Some(version) = version {
+            candidates.push(format!("python{}", minor_version(version)));
+        }
+        candidates.extend(["python3".to_string(), "python".to_string()]);
+        for candidate in candidates {
+            let path = home.join(candidate);
+            if path.is_file() {
+                return PythonInterpreter::new(&path.display().to_string());
+            }
+        }
+        bail!("No python interpreter in {}", home.display());
+    }
+
+    pub fn create_venv(&self, path: &PathBuf, options: &[String]) -> Result<()> {
         info!(
-            "Executing {} -m venv {}",
+            "Executing {} -m venv {}{}",
             self.interpreter,
+            options.iter().map(|o| format!("{o} ")).collect::<String>(),
             &path.as_path().display()
         );
-        let status = process::Command::new(self.interpreter)
+        let status = process::Command::new(&self.interpreter)
             .arg("-m")
             .arg("venv")
+            .args(options)
             .arg(path)
+            .stdout(Stdio::from(io::stderr()))
             .status()
             .context("Error spawning interpreter")?;
 
@@ -38,7 +164,83 @@ impl<'a> PythonInterpreter<'a> {
         Ok(())
     }
 
-    fn detect_version(interpreter: &String) -> Result<String> {
+    pub fn upgrade_venv(&self, path: &Path) -> Result<()> {
+        // venv follows the existing bin/python* links, so dangling ones must be removed first
+        for link in glob::glob(&format!("{}/bin/python*", path.display()))
+            .context("Unable to list python executables")?
+            .flatten()
+            .filter(|link| link.is_symlink() && fs::metadata(link).is_err())
+        {
+            fs::remove_file(&link)
+                .with_context(|| format!("Unable to delete {}", link.display()))?;
+        }
+        info!(
+            "Executing {} -m venv --upgrade {}",
+            self.interpreter,
+            path.display()
+        );
+        let status = process::Command::new(&self.interpreter)
+            .arg("-m")
+            .arg("venv")
+            .arg("--upgrade")
+            .arg(path)
+            .stdout(Stdio::from(io::stderr()))
+            .status()
+            .context("Error spawning interpreter")?;
+
+        if status.code().unwrap_or(1) != 0 {
+            bail!("Error upgrading venv {}", path.display());
+        }
+
+        Ok(())
+    }
+
+    pub fn pip_install(venv_path: &Path, packages: &[String]) -> Result<()> {
+        let python = venv_path.join("bin").join("python");
+        info!(
+            "Installing {} into {}",
+            packages.join(" "),
+            venv_path.display()
+        );
+        let status = process::Command::new(&python)
+            .arg("-m")
+            .arg("pip")
+            .arg("install")
+            .args(packages)
+            .stdout(Stdio::from(io::stderr()))
+            .status()
+            .context("Error spawning pip")?;
+
+        if status.code().unwrap_or(1) != 0 {
+            bail!("Error installing packages into {}", venv_path.display());
+        }
+
+        Ok(())
+    }
+
+    // Checks that the venv works with its interpreter
+    pub fn verify_venv(path: &Path) -> Result<()> {
+        let python = path.join("bin").join("python");
+        let output = process::Command::new(&python)
+            .arg("-c")
+            .arg("import encodings, site, sys; print(sys.prefix)")
+            .output()
+            .context("Unable to spawn interpreter")?;
+        if output.status.code().unwrap_or(1) != 0 {
+            bail!(
+                "{} failed: {}",
+                python.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        let prefix = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if fs::canonicalize(&prefix).ok() != fs::canonicalize(path).ok() {
+            bail!("{} uses unexpected prefix {prefix}", python.display());
+        }
+        Ok(())
+    }
+
+    fn detect_version(interpreter: &str) -> Result<String> {
         info!("Detecting python version");
         let output = process::Command::new(interpreter)
             .arg("-c")
@@ -59,3 +261,21 @@ impl<'a> PythonInterpreter<'a> {
         Ok(version)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("3.11.9"), vec![3, 11, 9]);
+        assert_eq!(parse_version("3.12.1.final.0"), vec![3, 12, 1]);
+        assert!(parse_version("3.9.9") < parse_version("3.11.1"));
+    }
+
+    #[test]
+    fn test_minor_version() {
+        assert_eq!(minor_version("3.11.9"), "3.11");
+        assert_eq!(minor_version("3.12"), "3.12");
+    }
+}
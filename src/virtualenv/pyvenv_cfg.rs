@@ -0,0 +1,108 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+use super::utils::replace_file;
+
+// Key-value content of pyvenv.cfg in the order it is stored in the file
+#[derive(Debug)]
+pub struct PyvenvCfg {
+    pub entries: Vec<(String, String)>,
+}
+
+impl PyvenvCfg {
+    pub fn read(venv: &Path) -> Result<Self> {
+        let content = fs::read_to_string(venv.join("pyvenv.cfg"))
+            .with_context(|| format!("Unable to read pyvenv.cfg in {}", venv.display()))?;
+        Ok(PyvenvCfg::parse(&content))
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let entries = content
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .collect();
+        PyvenvCfg { entries }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) {
+        match self.entries.iter_mut().find(|(k, _)| k == key) {
+            Some(entry) => entry.1 = value.to_string(),
+            None => self.entries.push((key.to_string(), value.to_string())),
+        }
+    }
+
+    pub fn write(&self, venv: &Path) -> Result<()> {
+        let content: String = self
+            .entries
+            .iter()
+            .map(|(key, value)| format!("{key} = {value}\n"))
+            .collect();
+        replace_file(&venv.join("pyvenv.cfg"), content)
+            .with_context(|| format!("Unable to write pyvenv.cfg in {}", venv.display()))
+    }
+
+    pub fn home(&self) -> Option<PathBuf> {
+        self.get("home").map(PathBuf::from)
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.get("version").or_else(|| self.get("version_info"))
+    }
+}
+
+// Checks that version matches prefix by components, i.e. 3.1 matches 3.1.2 but not 3.11.2
+pub fn version_matches(version: &str, prefix: &str) -> bool {
+    version == prefix || version.starts_with(&format!("{prefix}."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let cfg = PyvenvCfg::parse(
+            "home = /usr/bin\ninclude-system-site-packages = false\nversion = 3.11.7\n",
+        );
+        assert_eq!(cfg.get("home"), Some("/usr/bin"));
+        assert_eq!(cfg.home(), Some(PathBuf::from("/usr/bin")));
+        assert_eq!(cfg.version(), Some("3.11.7"));
+        assert_eq!(cfg.get("missing"), None);
+    }
+
+    #[test]
+    fn test_set() {
+        let mut cfg = PyvenvCfg::parse("home = /usr/bin\nversion = 3.11.7\n");
+        cfg.set("version", "3.11.9");
+        cfg.set("executable", "/usr/bin/python3.11");
+        assert_eq!(cfg.version(), Some("3.11.9"));
+        assert_eq!(cfg.entries.len(), 3);
+    }
+
+    #[test]
+    fn test_version_info() {
+        let cfg = PyvenvCfg::parse("home = /usr/bin\nversion_info = 3.12.1.final.0\n");
+        assert_eq!(cfg.version(), Some("3.12.1.final.0"));
+    }
+
+    #[test]
+    fn test_version_matches() {
+        assert!(version_matches("3.11.7", "3.11"));
+        assert!(version_matches("3.11.7", "3.11.7"));
+        assert!(version_matches("3.11.7", "3"));
+        assert!(!version_matches("3.11.7", "3.1"));
+        assert!(!version_matches("3.11.7", "3.12"));
+    }
+}
@@ -0,0 +1,222 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use simplelog::debug;
+
+use super::pyvenv_cfg::PyvenvCfg;
+
+fn relocatable_files(venv: &Path) -> Vec<PathBuf> {
+    let mut files = vec![venv.join("pyvenv.cfg")];
+    if let Ok(entries) = fs::read_dir(venv.join("bin")) {
+        files.extend(entries.flatten().map(|entry| entry.path()));
+    }
+    files
+        .into_iter()
+        .filter(|file| fs::symlink_metadata(file).is_ok_and(|m| m.is_file()))
+        .collect()
+}
+
+fn prompt(path: &Path) -> String {
+    format!("({}) ", name(path))
+}
+
+fn name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+}
+
+// Since python 3.13 activation scripts set VIRTUAL_ENV_PROMPT to the shell-quoted name
+// without parentheses, e.g. VIRTUAL_ENV_PROMPT=name or set -gx VIRTUAL_ENV_PROMPT 'name'
+fn replace_bare_prompt(content: &str, old: &str, new: &str) -> String {
+    content
+        .split_inclusive('\n')
+        .map(|line| {
+            let value = line.trim_end();
+            if !value.contains("VIRTUAL_ENV_PROMPT") {
+                return line.to_string();
+            }
+            for quote in ["", "'", "\""] {
+                for separator in ["=", " "] {
+                    let old_value = format!("{separator}{quote}{old}{quote}");
+                    if let Some(start) = value.strip_suffix(&old_value) {
+                        let new_value = format!("{separator}{quote}{new}{quote}");
+                        return format!("{start}{new_value}{}", &line[value.len()..]);
+                    }
+                }
+            }
+            line.to_string()
+        })
+        .collect()
+}
+
+// Path the venv was created at, as recorded in bin/activate or in the command of pyvenv.cfg.
+// It differs from the canonical path when the venv is reached through a symlink
+pub fn recorded_path(venv: &Path) -> Option<PathBuf> {
+    let activate = fs::read_to_string(venv.join("bin").join("activate")).unwrap_or_default();
+    let from_activate = activate.lines().find_map(|line| {
+        let value = line.trim().trim_start_matches("export ");
+        let value = value
+            .strip_prefix("VIRTUAL_ENV=")?
+            .trim_matches(['"', '\'']);
+        // Python 3.13 converts the path with cygpath on Windows shells
+        (!value.starts_with('$')).then(|| PathBuf::from(value))
+    });
+    from_activate.or_else(|| {
+        let cfg = PyvenvCfg::read(venv).ok()?;
+        cfg.get("command")?
+            .split_whitespace()
+            .last()
+            .map(PathBuf::from)
+    })
+}
+
+// Replaces the old venv path with the new one in pyvenv.cfg, activation scripts and
+// shebangs of the console scripts, and the default prompt in activation scripts
+// in both the "(name) " form and the bare name form of python 3.13.
+// Files are replaced, not modified in place, so hardlinked copies are not affected.
+pub fn relocate(venv: &Path, old: &Path, new: &Path) -> Result<usize> {
+    let (old_prompt, new_prompt) = (prompt(old), prompt(new));
+    let (old_name, new_name) = (name(old), name(new));
+    let old = old.display().to_string();
+    let new = new.display().to_string();
+    let mut relocated = 0;
+    for file in relocatable_files(venv) {
+        // Binary files are skipped
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        let mut relocated_content = content.replace(&old, &new);
+        if file.file_name().is_some_and(|name| {
+            name.to_string_lossy()
+                .to_lowercase()
+                .starts_with("activate")
+        }) {
+            relocated_content = relocated_content.replace(&old_prompt, &new_prompt);
+            relocated_content = replace_bare_prompt(&relocated_content, &old_name, &new_name);
+        }
+        if relocated_content == content {
+            continue;
+        }
+        let permissions = fs::metadata(&file)
+            .context("Unable to read file permissions")?
+            .permissions();
+        let tmp = file.with_extension("rsvenv-tmp");
+        fs::write(&tmp, relocated_content)
+            .with_context(|| format!("Unable to write {}", tmp.display()))?;
+        fs::set_permissions(&tmp, permissions).context("Unable to set file permissions")?;
+        fs::rename(&tmp, &file).with_context(|| format!("Unable to replace {}", file.display()))?;
+        debug!("Relocated {}", file.display());
+        relocated += 1;
+    }
+    Ok(relocated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::virtualenv::utils::copy_tree;
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    #[test]
+    fn test_relocate() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let old = dir.as_ref().join("old");
+        let new = dir.as_ref().join("new");
+        let bin = new.join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        fs::write(
+            bin.join("activate"),
+            format!(
+                "VIRTUAL_ENV=\"{}\"\nPS1=\"(old) ${{PS1:-}}\"\n",
+                old.display()
+            ),
+        )
+        .unwrap();
+        fs::write(
+            bin.join("activate.fish"),
+            "set -gx VIRTUAL_ENV_PROMPT 'old'\necho 'old'\n",
+        )
+        .unwrap();
+        fs::write(
+            bin.join("activate.csh"),
+            "setenv VIRTUAL_ENV_PROMPT old\nVIRTUAL_ENV_PROMPT=old\n",
+        )
+        .unwrap();
+        let script = bin.join("black");
+        fs::write(
+            &script,
+            format!("#!{}/bin/python\nimport black\n", old.display()),
+        )
+        .unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        let hardlink = dir.as_ref().join("hardlink");
+        fs::hard_link(&script, &hardlink).unwrap();
+        fs::write(new.join("pyvenv.cfg"), "home = /usr/bin\n").unwrap();
+
+        assert_eq!(relocate(&new, &old, &new).unwrap(), 4);
+        assert_eq!(
+            fs::read_to_string(bin.join("activate")).unwrap(),
+            format!(
+                "VIRTUAL_ENV=\"{}\"\nPS1=\"(new) ${{PS1:-}}\"\n",
+                new.display()
+            )
+        );
+        // Only the prompt value is replaced in the bare form
+        assert_eq!(
+            fs::read_to_string(bin.join("activate.fish")).unwrap(),
+            "set -gx VIRTUAL_ENV_PROMPT 'new'\necho 'old'\n"
+        );
+        assert_eq!(
+            fs::read_to_string(bin.join("activate.csh")).unwrap(),
+            "setenv VIRTUAL_ENV_PROMPT new\nVIRTUAL_ENV_PROMPT=new\n"
+        );
+        assert!(fs::read_to_string(&script)
+            .unwrap()
+            .starts_with(&format!("#!{}/bin/python", new.display())));
+        assert_eq!(fs::metadata(&script).unwrap().mode() & 0o777, 0o755);
+        // the hardlinked copy keeps the old path
+        assert!(fs::read_to_string(&hardlink)
+            .unwrap()
+            .starts_with(&format!("#!{}/bin/python", old.display())));
+    }
+
+    #[test]
+    fn test_relocate_symlinked_root() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let real = dir.as_ref().join("real");
+        let link = dir.as_ref().join("link");
+        fs::create_dir_all(real.join("venvs").join("source").join("bin")).unwrap();
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+        // The venv was created through the symlinked store root
+        let source = link.join("venvs").join("source");
+        fs::write(
+            source.join("bin").join("activate"),
+            format!("export VIRTUAL_ENV=\"{}\"\n", source.display()),
+        )
+        .unwrap();
+
+        let canonical = fs::canonicalize(&source).unwrap();
+        assert_eq!(recorded_path(&canonical), Some(source.clone()));
+        let clone = link.join("venvs").join("clone");
+        copy_tree(&canonical, &clone, true).unwrap();
+        let old = recorded_path(&canonical).unwrap();
+        assert_eq!(relocate(&clone, &old, &clone).unwrap(), 1);
+        assert_eq!(
+            fs::read_to_string(clone.join("bin").join("activate")).unwrap(),
+            format!("export VIRTUAL_ENV=\"{}\"\n", clone.display())
+        );
+
+        fs::remove_file(clone.join("bin").join("activate")).unwrap();
+        fs::write(
+            clone.join("pyvenv.cfg"),
+            format!("command = /usr/bin/python3 -m venv {}\n", source.display()),
+        )
+        .unwrap();
+        assert_eq!(recorded_path(&clone), Some(source));
+    }
+}
@@ -0,0 +1,134 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
+use simplelog::{debug, info, warn};
+
+use super::{
+    python::{minor_version, PythonInterpreter},
+    pyvenv_cfg::PyvenvCfg,
+};
+
+fn find_interpreter(cfg: &PyvenvCfg, python: Option<&str>) -> Result<PythonInterpreter> {
+    let version = cfg.version().context("No python version in pyvenv.cfg")?;
+    let minor = minor_version(version);
+    if let Some(spec) = python {
+        return PythonInterpreter::find(spec);
+    }
+    if let Some(home) = cfg.home() {
+        match PythonInterpreter::from_home(&home, Some(version)) {
+            Ok(interpreter) if interpreter.minor_version() == minor => return Ok(interpreter),
+            Ok(interpreter) => debug!("{} is python {}", home.display(), interpreter.version),
+            Err(e) => debug!("{e:?}"),
+        }
+    }
+    PythonInterpreter::find(&minor)
+}
+
+// Points the venv to an interpreter of the same minor version and checks that it works
+pub fn repair(path: &Path, python: Option<&str>) -> Result<PythonInterpreter> {
+    let mut cfg = PyvenvCfg::read(path)?;
+    let interpreter = find_interpreter(&cfg, python)?;
+    let minor = minor_version(cfg.version().unwrap_or_default());
+    if interpreter.minor_version() != minor {
+        bail!(
+            "{} is python {}, but the virtual environment requires {minor}",
+            interpreter.interpreter,
+            interpreter.version
+        );
+    }
+    info!(
+        "Repairing {} with {} ({})",
+        path.display(),
+        interpreter.interpreter,
+        interpreter.version
+    );
+
+    let executable = interpreter.executable()?;
+    let home = executable
+        .parent()
+        .context("Unable to get interpreter directory")?;
+    let original_cfg = PyvenvCfg::read(path)?;
+    let original_links = python_links(path)?
+        .into_iter()
+        .filter_map(|link| fs::read_link(&link).ok().map(|target| (link, target)))
+        .collect_vec();
+    cfg.set("home", &home.display().to_string());
+    cfg.set("version", &interpreter.version);
+    if cfg.get("executable").is_some() {
+        cfg.set("executable", &executable.display().to_string());
+    }
+
+    let result = cfg
+        .write(path)
+        .and_then(|_| remove_links(path))
+        .and_then(|_| interpreter.upgrade_venv(path))
+        .and_then(|_| PythonInterpreter::verify_venv(path));
+    if let Err(e) = result {
+        warn!("Repairing {} failed, restoring it", path.display());
+        restore(path, &original_cfg, &original_links)
+            .with_context(|| format!("Unable to restore {}", path.display()))?;
+        return Err(e);
+    }
+    Ok(interpreter)
+}
+
+fn python_links(path: &Path) -> Result<Vec<PathBuf>> {
+    Ok(glob::glob(&format!("{}/bin/python*", path.display()))
+        .context("Unable to list python executables")?
+        .flatten()
+        .filter(|link| link.is_symlink())
+        .collect())
+}
+
+fn remove_links(path: &Path) -> Result<()> {
+    for link in python_links(path)? {
+        fs::remove_file(&link).with_context(|| format!("Unable to delete {}", link.display()))?;
+    }
+    Ok(())
+}
+
+// Puts back pyvenv.cfg and python links the venv had before the repair
+fn restore(path: &Path, cfg: &PyvenvCfg, links: &[(PathBuf, PathBuf)]) -> Result<()> {
+    cfg.write(path)?;
+    remove_links(path)?;
+    for (link, target) in links {
+        std::os::unix::fs::symlink(target, link)
+            .with_context(|| format!("Unable to create symlink {}", link.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let venv = dir.path();
+        fs::create_dir_all(venv.join("bin")).unwrap();
+        let cfg = PyvenvCfg::parse("home = /usr/bin\nversion = 3.11.7\n");
+        let links = vec![(venv.join("bin").join("python"), PathBuf::from("python3"))];
+
+        PyvenvCfg::parse("home = /opt/bin\nversion = 3.11.9\n")
+            .write(venv)
+            .unwrap();
+        std::os::unix::fs::symlink("/opt/bin/python3.11", venv.join("bin").join("python3.11"))
+            .unwrap();
+        restore(venv, &cfg, &links).unwrap();
+
+        assert_eq!(PyvenvCfg::read(venv).unwrap().version(), Some("3.11.7"));
+        assert_eq!(
+            python_links(venv).unwrap(),
+            vec![venv.join("bin").join("python")]
+        );
+        assert_eq!(
+            fs::read_link(venv.join("bin").join("python")).unwrap(),
+            PathBuf::from("python3")
+        );
+    }
+}
@@ -4,16 +4,23 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use crate::configuration::SETTINGS;
-use anyhow::{anyhow, bail, Context, Result};
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
 use regex::Regex;
 use simplelog::info;
 use std::io::Write;
 
 use super::{
-    python::PythonInterpreter,
+    aliases::Aliases,
+    locks::Lock,
+    projects::Projects,
+    python::{parse_version, PythonInterpreter},
+    pyvenv_cfg::PyvenvCfg,
+    relocate::{recorded_path, relocate},
+    snapshots::Snapshots,
     traits::VirtualEnvCompatible,
-    utils::{get_current_dir, get_venvs_by_glob},
+    trash::Trash,
+    utils::{copy_tree, get_current_dir, get_rsvenv_home, get_venvs_by_glob, LAST_USED_MARKER},
 };
 
 #[derive(Debug)]
@@ -29,57 +36,152 @@ impl Rsenv {
         bail!("name {name} is invalid");
     }
 
-    pub fn create(&self, name: &String, python: &String) -> Result<()> {
-        let interpreter = PythonInterpreter::new(python)?;
+    // Reserves the directory of a new venv in the store. The store lock is held only while
+    // checking and creating the directory, the returned venv lock guards filling it
+    pub fn reserve(&self, name: &String) -> Result<(PathBuf, Lock)> {
+        Rsenv::validate_name(name)?;
+        let _store_lock = Lock::store()?;
+        let path = self.root_dir()?.join(name);
+        if self.list().contains(name) || path.exists() {
+            bail!("Virtual environment {name} exists");
+        }
+        let lock = Lock::venv(name)?;
+        create_dir_all(&path).context("Unable to create directory for virtual env")?;
+        Ok((path, lock))
+    }
 
+    pub fn create(&self, name: &String, python: &str, options: &[String]) -> Result<String> {
+        let interpreter = PythonInterpreter::find(python)?;
         let name_with_version = format!("{}/{}", &interpreter.version, name);
-        let existing = self.list();
+        let (venv_path, _lock) = {
+            let _store_lock = Lock::store()?;
+            Rsenv::validate_name(name)?;
+            if self.list().contains(name) {
+                bail!("Virtual environment {name} exists");
+            }
+            self.reserve(&name_with_version)?
+        };
+        self.create_reserved(&venv_path, &interpreter, options)?;
+        info!("Created venv {name_with_version}");
+        Ok(name_with_version)
+    }
 
-        Rsenv::validate_name(name)?;
-        if existing.contains(name) || existing.contains(&name_with_version) {
-            bail!("Virtual environment {name} exists");
+    // Creates a venv in the directory returned by reserve, removing it on failure
+    pub fn create_reserved(
+        &self,
+        path: &Path,
+        interpreter: &PythonInterpreter,
+        options: &[String],
+    ) -> Result<()> {
+        if let Err(e) = interpreter.create_venv(&path.to_path_buf(), options) {
+            fs::remove_dir_all(path).unwrap_or_default();
+            bail!(e);
         }
+        Ok(())
+    }
 
-        let root_dir = self.root_dir()?;
+    pub fn install(&self, name: &String, packages: &[String]) -> Result<()> {
+        if packages.is_empty() {
+            return Ok(());
+        }
+        let path = self.path(Some(name))?;
+        PythonInterpreter::pip_install(&path, packages)?;
+        info!("Installed packages into {name}");
+        Ok(())
+    }
 
-        let path = root_dir.as_path();
-        if !path.exists() {
-            create_dir_all(path).context("Unable to create root directory for virtual env")?;
-            info!("Created root dir");
+    // Name in the store for a venv derived from the source one: a name without
+    // a python version inherits the version of the source
+    pub fn target_name(source: &str, name: &str) -> String {
+        match source.split_once('/') {
+            Some((version, _)) if !name.contains('/') => format!("{version}/{name}"),
+            _ => name.to_string(),
         }
+    }
 
-        let venv_path = path.join(&interpreter.version).join(name);
-        interpreter.create_venv(&venv_path)?;
-        info!("Created venv {name_with_version}");
+    // Name in the store for a venv of another backend, whose names don't start with
+    // the python version: the version from pyvenv.cfg of the venv is prepended
+    pub fn versioned_name(venv: &Path, name: &str) -> Result<String> {
+        match PyvenvCfg::read(venv)?.version() {
+            Some(version) => Ok(format!(
+                "{}/{name}",
+                parse_version(version).iter().join(".")
+            )),
+            None => bail!("Unable to detect python version of {}", venv.display()),
+        }
+    }
+
+    pub fn rename(&self, old: &String, new: &str) -> Result<String> {
+        Rsenv::validate_name(old)?;
+        Rsenv::validate_name(new)?;
+        let _store_lock = Lock::store()?;
+        let _lock = Lock::venv(old)?;
+        let existing = self.list();
+        if !existing.contains(old) {
+            bail!("{old} is not managed by rsenv");
+        }
+        let new = Rsenv::target_name(old, new);
+        let root_dir = self.root_dir()?;
+        let old_path = root_dir.join(old);
+        let new_path = root_dir.join(&new);
+        // The directory of a venv being created exists before it is listed
+        if existing.contains(&new) || new_path.exists() {
+            bail!("Virtual environment {new} exists");
+        }
+        let _new_lock = Lock::venv(&new)?;
+        if let Some(parent) = new_path.parent() {
+            create_dir_all(parent).context("Unable to create root directory for virtual env")?;
+        }
+        fs::rename(&old_path, &new_path).context("Unable to move virtual env")?;
+        relocate(&new_path, &old_path, &new_path)?;
+        Snapshots::new(old).rename(&new, &old_path, &new_path)?;
+        info!("Renamed venv {old} to {new}");
+        Aliases::repoint(old, &new)?;
+        Ok(new)
+    }
+
+    // Copies the venv into the store (using hardlinks where possible) under the given name
+    pub fn clone_from(&self, source: &Path, name: &String) -> Result<()> {
+        Rsenv::validate_name(name)?;
+        let (path, _lock) = self.reserve(name)?;
+        if let Err(e) = copy_tree(source, &path, true) {
+            fs::remove_dir_all(&path).unwrap_or_default();
+            bail!(e);
+        }
+        // The marker is a hardlink to the one of the source, and the clone has never been used
+        fs::remove_file(path.join(LAST_USED_MARKER)).unwrap_or_default();
+        // The source may be a canonical path, while its files use the path it was created at
+        let old = recorded_path(source).unwrap_or(source.to_path_buf());
+        relocate(&path, &old, &path)?;
+        info!("Cloned {} to {name}", source.display());
         Ok(())
     }
 
-    pub fn delete(&self, name: String) -> Result<()> {
-        Rsenv::validate_name(&name)?;
-        if !self.list().contains(&name) {
-            bail!(
-                "Cannot delete virtual environment: {} is not managed by rsenv",
-                name
-            );
+    pub fn restore(&self, name: &String) -> Result<()> {
+        Rsenv::validate_name(name)?;
+        let _store_lock = Lock::store()?;
+        let path = self.root_dir()?.join(name);
+        if self.list().contains(name) || path.exists() {
+            bail!("Virtual environment {name} exists");
         }
-        fs::remove_dir_all(self.root_dir()?.join(name.clone()).as_path())
-            .context("Unable to delete virtual env")?;
-        info!("Deleted venv {}", name);
+        let _lock = Lock::venv(name)?;
+        Trash::new().restore(name, &path)?;
+        info!("Restored venv {name}");
         Ok(())
     }
 }
 
 impl VirtualEnvCompatible for Rsenv {
+    fn backend(&self) -> &'static str {
+        "rsenv"
+    }
+
     fn root_dir(&self) -> Result<PathBuf> {
-        let expanded = shellexpand::full(
-            &SETTINGS
-                .read()
-                .map_err(|e| anyhow!("Unable to read path from SETTINGS: {e:?}"))?
-                .path,
-        )
-        .context("unable to expand SETTINGS.path to the actual path")?
-        .to_string();
-        Ok(Path::new(&expanded).to_path_buf().join("venvs"))
+        Ok(get_rsvenv_home()?.join("venvs"))
+    }
+
+    fn resolve(&self, name: &str) -> String {
+        Aliases::resolve(name)
     }
 
     fn list(&self) -> HashSet<String> {
@@ -91,6 +193,10 @@ impl VirtualEnvCompatible for Rsenv {
         HashSet::new()
     }
 
+    fn has_name_shape(&self, name: &str) -> bool {
+        !name.is_empty() && Rsenv::validate_name(name).is_ok()
+    }
+
     fn relevant(&self) -> bool {
         if std::env::current_dir().is_err() {
             return false;
@@ -112,13 +218,39 @@ impl VirtualEnvCompatible for Rsenv {
         )
     }
 
+    fn delete(&self, name: &String, permanent: bool) -> Result<()> {
+        let name = &self.resolve(name);
+        Rsenv::validate_name(name)?;
+        // Removing a venv needs only its own lock, so a process holding it (e.g. upgrade)
+        // never waits for the store lock after a venv lock
+        let _lock = Lock::venv(name)?;
+        if !self.list().contains(name) {
+            bail!(
+                "Cannot delete virtual environment: {} is not managed by rsenv",
+                name
+            );
+        }
+        let path = self.root_dir()?.join(name);
+        if !permanent {
+            return Trash::new().put(name, &path);
+        }
+        fs::remove_dir_all(path.as_path()).context("Unable to delete virtual env")?;
+        Snapshots::new(name).delete_all()?;
+        info!("Deleted venv {}", name);
+        Ok(())
+    }
+
+    fn supports_trash(&self) -> bool {
+        true
+    }
+
     fn save(&self, name: &str) -> Result<()> {
         let _ = File::create(".python-virtualenv")
             .context("Unable to create .python-virtualenv")?
             .write_all(name.as_bytes());
         info!("Saved changes to .python-virtualenv");
         fs::remove_file(".python-version").unwrap_or_default();
-        Ok(())
+        Projects::register(&get_current_dir()?)
     }
 }
 
@@ -133,6 +265,24 @@ mod tests {
         assert!(Rsenv::validate_name(&String::from("Good_nAme")).is_ok());
         assert!(Rsenv::validate_name(&String::from("Good_nAme/asdfadsf")).is_ok());
     }
+    #[test]
+    fn test_target_name() {
+        assert_eq!(Rsenv::target_name("3.11.7/proj", "copy"), "3.11.7/copy");
+        assert_eq!(
+            Rsenv::target_name("3.11.7/proj", "3.12.1/copy"),
+            "3.12.1/copy"
+        );
+        assert_eq!(Rsenv::target_name("proj", "copy"), "copy");
+    }
+
+    #[test]
+    fn test_name_shape() {
+        assert!(Rsenv.has_name_shape("3.11.7/proj"));
+        assert!(Rsenv.has_name_shape("proj"));
+        assert!(!Rsenv.has_name_shape("3.11.7/proj/lib"));
+        assert!(!Rsenv.has_name_shape(""));
+    }
+
     #[test]
     fn test_bad_name() {
         assert!(Rsenv::validate_name(&String::from("bad!name")).is_err());
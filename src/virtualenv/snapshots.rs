@@ -0,0 +1,266 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
+use regex::Regex;
+use simplelog::{info, warn};
+
+use super::{
+    relocate::relocate,
+    utils::{copy_tree, get_rsvenv_home},
+};
+
+#[derive(Debug)]
+pub struct Snapshot {
+    pub label: String,
+    pub created_at: SystemTime,
+    pub path: PathBuf,
+}
+
+// Snapshots of a venv are hardlink trees stored in
+// <rsvenv home>/snapshots/<venv name>/<label>/venv with the creation time (ms) in <label>/created
+#[derive(Debug)]
+pub struct Snapshots<'a> {
+    pub name: &'a str,
+    pub(super) home: Option<PathBuf>,
+}
+
+impl<'a> Snapshots<'a> {
+    pub fn new(name: &'a str) -> Self {
+        Snapshots { name, home: None }
+    }
+
+    fn root(&self) -> Result<PathBuf> {
+        let home = match &self.home {
+            Some(home) => home.clone(),
+            None => get_rsvenv_home()?,
+        };
+        Ok(home.join("snapshots").join(self.name))
+    }
+
+    pub fn validate_label(label: &str) -> Result<()> {
+        if Regex::new(r"^[\w.\-]+$").unwrap().is_match(label) {
+            return Ok(());
+        }
+        bail!("label {label} is invalid");
+    }
+
+    // Default label is the creation time, e.g. 20240101T120000
+    fn default_label(time: SystemTime) -> String {
+        humantime::format_rfc3339_seconds(time)
+            .to_string()
+            .chars()
+            .filter(|c| c.is_ascii_digit() || *c == 'T')
+            .collect()
+    }
+
+    // Sorted from the oldest to the most recent
+    pub fn list(&self) -> Vec<Snapshot> {
+        let Ok(root) = self.root() else {
+            return vec![];
+        };
+        let Ok(entries) = fs::read_dir(root) else {
+            return vec![];
+        };
+        entries
+            .flatten()
+            .filter_map(|entry| {
+                let millis = fs::read_to_string(entry.path().join("created"))
+                    .ok()?
+                    .trim()
+                    .parse()
+                    .ok()?;
+                Some(Snapshot {
+                    label: entry.file_name().to_str()?.to_string(),
+                    created_at: UNIX_EPOCH + Duration::from_millis(millis),
+                    path: entry.path().join("venv"),
+                })
+            })
+            .sorted_by_key(|snapshot| snapshot.created_at)
+            .collect()
+    }
+
+    pub fn get(&self, label: Option<&String>) -> Result<Snapshot> {
+        let snapshot = match label {
+            Some(label) => self.list().into_iter().find(|s| &s.label == label),
+            None => self.list().pop(),
+        };
+        snapshot.with_context(|| {
+            format!(
+                "Snapshot {} of {} doesn't exist",
+                label.map(String::as_str).unwrap_or("latest"),
+                self.name
+            )
+        })
+    }
+
+    pub fn create(&self, venv: &Path, label: Option<&String>) -> Result<String> {
+        let now = SystemTime::now();
+        let label = match label {
+            Some(label) => label.clone(),
+            None => Snapshots::default_label(now),
+        };
+        Snapshots::validate_label(&label)?;
+        let dir = self.root()?.join(&label);
+        if dir.exists() {
+            bail!("Snapshot {label} of {} exists", self.name);
+        }
+        let created = now
+            .duration_since(UNIX_EPOCH)
+            .context("System time is before UNIX epoch")?
+            .as_millis();
+        if let Err(e) = copy_tree(venv, &dir.join("venv"), true)
+            .and_then(|_| fs::write(dir.join("created"), created.to_string()).map_err(Into::into))
+        {
+            fs::remove_dir_all(&dir).unwrap_or_default();
+            bail!(e);
+        }
+        info!("Created snapshot {label} of {}", self.name);
+        Ok(label)
+    }
+
+    // Replaces the venv with a copy of the snapshot, the snapshot itself is kept.
+    // The copy and the replaced venv are kept next to the snapshots, outside of the store
+    pub fn rollback(&self, venv: &Path, label: Option<&String>) -> Result<()> {
+        let snapshot = self.get(label)?;
+        let root = self.root()?.display().to_string();
+        let restored = PathBuf::from(format!("{root}.rsvenv-rollback"));
+        let replaced = PathBuf::from(format!("{root}.rsvenv-replaced"));
+        // Left over by an interrupted rollback
+        fs::remove_dir_all(&restored).unwrap_or_default();
+        fs::remove_dir_all(&replaced).unwrap_or_default();
+
+        if let Err(e) = copy_tree(&snapshot.path, &restored, true) {
+            fs::remove_dir_all(&restored).unwrap_or_default();
+            bail!(e);
+        }
+        if let Err(e) = fs::rename(venv, &replaced) {
+            fs::remove_dir_all(&restored).unwrap_or_default();
+            return Err(e).context("Unable to move virtual env aside");
+        }
+        if let Err(e) = fs::rename(&restored, venv) {
+            fs::rename(&replaced, venv).context("Unable to put virtual env back")?;
+            fs::remove_dir_all(&restored).unwrap_or_default();
+            return Err(e).context("Unable to move restored virtual env");
+        }
+        if let Err(e) = fs::remove_dir_all(&replaced) {
+            warn!("Unable to delete {}: {e}", replaced.display());
+        }
+        info!("Rolled back {} to snapshot {}", self.name, snapshot.label);
+        Ok(())
+    }
+
+    // Moves the snapshots of a renamed venv and relocates them to its new path
+    pub fn rename(&self, new_name: &str, old_path: &Path, new_path: &Path) -> Result<()> {
+        let root = self.root()?;
+        if !root.exists() {
+            return Ok(());
+        }
+        let renamed = Snapshots {
+            name: new_name,
+            home: self.home.clone(),
+        };
+        let new_root = renamed.root()?;
+        if let Some(parent) = new_root.parent() {
+            fs::create_dir_all(parent).context("Unable to create snapshots directory")?;
+        }
+        fs::rename(&root, &new_root).context("Unable to move snapshots")?;
+        for snapshot in renamed.list() {
+            relocate(&snapshot.path, old_path, new_path)?;
+        }
+        Ok(())
+    }
+
+    // Deletes all snapshots, e.g. when the venv is deleted permanently
+    pub fn delete_all(&self) -> Result<()> {
+        let root = self.root()?;
+        if !root.exists() {
+            return Ok(());
+        }
+        fs::remove_dir_all(&root).context("Unable to delete snapshots")?;
+        // The version directory of the venv name is removed when it is empty
+        if let Some(parent) = root.parent() {
+            fs::remove_dir(parent).unwrap_or_default();
+        }
+        info!("Deleted snapshots of {}", self.name);
+        Ok(())
+    }
+
+    pub fn delete(&self, label: &String) -> Result<()> {
+        let snapshot = self.get(Some(label))?;
+        let dir = snapshot.path.parent().context("Invalid snapshot path")?;
+        fs::remove_dir_all(dir).context("Unable to delete snapshot")?;
+        info!("Deleted snapshot {label} of {}", self.name);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_label() {
+        let time = UNIX_EPOCH + Duration::from_secs(1704110400);
+        assert_eq!(Snapshots::default_label(time), "20240101T120000");
+        assert!(Snapshots::validate_label(&Snapshots::default_label(time)).is_ok());
+    }
+
+    #[test]
+    fn test_create_rollback() {
+        use crate::virtualenv::{pyvenv_cfg::PyvenvCfg, utils::mark_used};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let venv = dir.path().join("venvs").join("3.11.7").join("project");
+        fs::create_dir_all(venv.join("bin")).unwrap();
+        fs::write(venv.join("bin").join("activate"), "").unwrap();
+        fs::write(
+            venv.join("pyvenv.cfg"),
+            "home = /usr/bin\nversion = 3.11.7\n",
+        )
+        .unwrap();
+        mark_used(&venv);
+        let snapshots = Snapshots {
+            name: "3.11.7/project",
+            home: Some(dir.path().to_path_buf()),
+        };
+        let label = "before".to_string();
+        snapshots.create(&venv, Some(&label)).unwrap();
+
+        let mut cfg = PyvenvCfg::read(&venv).unwrap();
+        cfg.set("home", "/opt/bin");
+        cfg.write(&venv).unwrap();
+        mark_used(&venv);
+        fs::write(venv.join("bin").join("black"), "").unwrap();
+        let snapshot = snapshots.get(Some(&label)).unwrap();
+        assert_eq!(
+            PyvenvCfg::read(&snapshot.path).unwrap().get("home"),
+            Some("/usr/bin")
+        );
+
+        snapshots.rollback(&venv, None).unwrap();
+        let root = snapshots.root().unwrap().display().to_string();
+        assert!(!PathBuf::from(format!("{root}.rsvenv-rollback")).exists());
+        assert!(!PathBuf::from(format!("{root}.rsvenv-replaced")).exists());
+        assert_eq!(
+            PyvenvCfg::read(&venv).unwrap().get("home"),
+            Some("/usr/bin")
+        );
+        assert!(!venv.join("bin").join("black").exists());
+        assert_eq!(snapshots.list().len(), 1);
+
+        snapshots.delete_all().unwrap();
+        assert!(snapshots.list().is_empty());
+    }
+
+    #[test]
+    fn test_validate_label() {
+        assert!(Snapshots::validate_label("before-upgrade_1.2").is_ok());
+        assert!(Snapshots::validate_label("../escape").is_err());
+        assert!(Snapshots::validate_label("").is_err());
+    }
+}
@@ -0,0 +1,123 @@
+use clap::ValueEnum;
+use itertools::Itertools;
+
+use super::distributions::Distribution;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SpecFormat {
+    Requirements,
+    Pyproject,
+    CondaYaml,
+}
+
+// Textual description of a venv that allows to reproduce it
+pub struct Spec<'a> {
+    pub name: &'a str,
+    pub python_version: &'a str,
+    pub distributions: &'a [Distribution],
+}
+
+impl Spec<'_> {
+    pub fn render(&self, format: SpecFormat) -> String {
+        let requirements = self
+            .distributions
+            .iter()
+            .filter(|d| !d.is_bootstrap())
+            .map(|d| d.requirement())
+            .collect_vec();
+        match format {
+            SpecFormat::Requirements => format!(
+                "# python {}\n{}",
+                self.python_version,
+                requirements.iter().map(|r| format!("{r}\n")).join("")
+            ),
+            SpecFormat::Pyproject => format!(
+                "[project]\nname = \"{}\"\nversion = \"0.1.0\"\nrequires-python = \"=={}.*\"\ndependencies = [\n{}]\n",
+                self.name,
+                self.python_version.split('.').take(2).join("."),
+                requirements
+                    .iter()
+                    .map(|r| format!("    \"{r}\",\n"))
+                    .join("")
+            ),
+            // Packages are installed with pip since PyPI names don't always match conda ones
+            SpecFormat::CondaYaml => format!(
+                "name: {}\ndependencies:\n  - python={}\n  - pip\n{}",
+                self.name,
+                self.python_version,
+                if requirements.is_empty() {
+                    String::new()
+                } else {
+                    format!(
+                        "  - pip:\n{}",
+                        requirements
+                            .iter()
+                            .map(|r| format!("      - {r}\n"))
+                            .join("")
+                    )
+                }
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distributions() -> Vec<Distribution> {
+        [
+            ("attrs", "23.1.0"),
+            ("pip", "23.2.1"),
+            ("requests", "2.31.0"),
+        ]
+        .into_iter()
+        .map(|(name, version)| Distribution {
+            name: name.to_string(),
+            version: version.to_string(),
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_requirements() {
+        let distributions = distributions();
+        let spec = Spec {
+            name: "proj",
+            python_version: "3.11.7",
+            distributions: &distributions,
+        };
+        assert_eq!(
+            spec.render(SpecFormat::Requirements),
+            "# python 3.11.7\nattrs==23.1.0\nrequests==2.31.0\n"
+        );
+    }
+
+    #[test]
+    fn test_pyproject() {
+        let distributions = distributions();
+        let spec = Spec {
+            name: "proj",
+            python_version: "3.11.7",
+            distributions: &distributions,
+        };
+        assert_eq!(
+            spec.render(SpecFormat::Pyproject),
+            "[project]\nname = \"proj\"\nversion = \"0.1.0\"\nrequires-python = \"==3.11.*\"\ndependencies = [\n    \"attrs==23.1.0\",\n    \"requests==2.31.0\",\n]\n"
+        );
+    }
+
+    #[test]
+    fn test_conda_yaml() {
+        let distributions = distributions();
+        let spec = Spec {
+            name: "proj",
+            python_version: "3.11.7",
+            distributions: &distributions,
+        };
+        assert_eq!(
+            spec.render(SpecFormat::CondaYaml),
+            "name: proj\ndependencies:\n  - python=3.11.7\n  - pip\n  - pip:\n      - attrs==23.1.0\n      - requests==2.31.0\n"
+        );
+    }
+}
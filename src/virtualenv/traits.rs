@@ -1,21 +1,41 @@
 use super::utils::is_virtualenv;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use std::{collections::HashSet, path::PathBuf};
 
 pub trait VirtualEnvCompatible {
+    fn backend(&self) -> &'static str;
+
     fn root_dir(&self) -> Result<PathBuf>;
 
     fn list(&self) -> HashSet<String> {
         HashSet::new()
     }
 
+    // Whether a path relative to root_dir has the shape of the names listed by the backend,
+    // checked instead of listing when the venv of a path is looked up on every prompt
+    fn has_name_shape(&self, _name: &str) -> bool {
+        false
+    }
+
+    // Name of the venv the given name refers to
+    fn resolve(&self, name: &str) -> String {
+        name.to_string()
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.list().contains(&self.resolve(name))
+    }
+
     fn relevant(&self) -> bool;
 
     fn venv_name(&self) -> Result<String>;
 
     fn path(&self, name: Option<&String>) -> Result<PathBuf> {
-        let venv_name = self.venv_name()?;
-        let b = self.root_dir()?.join(name.unwrap_or(&venv_name));
+        let venv_name = match name {
+            Some(name) => name.clone(),
+            None => self.venv_name()?,
+        };
+        let b = self.root_dir()?.join(self.resolve(&venv_name));
         is_virtualenv(&b)?;
         Ok(b)
     }
@@ -23,4 +43,12 @@ pub trait VirtualEnvCompatible {
     fn save(&self, _name: &str) -> Result<()> {
         Ok(())
     }
+
+    fn delete(&self, name: &String, _permanent: bool) -> Result<()> {
+        bail!("Deleting {name} is not supported by {}", self.backend());
+    }
+
+    fn supports_trash(&self) -> bool {
+        false
+    }
 }
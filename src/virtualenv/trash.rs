@@ -0,0 +1,216 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
+use simplelog::info;
+
+use super::{
+    rsenv::Rsenv,
+    snapshots::Snapshots,
+    traits::VirtualEnvCompatible,
+    utils::{get_rsvenv_home, get_venvs_by_glob},
+};
+
+#[derive(Debug)]
+pub struct TrashEntry {
+    pub name: String,
+    pub deleted_at: SystemTime,
+    pub path: PathBuf,
+}
+
+// Deleted virtual environments are kept in <rsvenv home>/trash/<deletion time in ms>/<name>
+#[derive(Debug, Default)]
+pub struct Trash {
+    home: Option<PathBuf>,
+}
+
+impl Trash {
+    pub fn new() -> Self {
+        Trash { home: None }
+    }
+
+    fn root(&self) -> Result<PathBuf> {
+        let home = match &self.home {
+            Some(home) => home.clone(),
+            None => get_rsvenv_home()?,
+        };
+        Ok(home.join("trash"))
+    }
+
+    pub fn put(&self, name: &str, path: &Path) -> Result<()> {
+        let mut timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("System time is before UNIX epoch")?
+            .as_millis();
+        // Venvs with the same name deleted within the same millisecond get distinct entries
+        while self.root()?.join(timestamp.to_string()).join(name).exists() {
+            timestamp += 1;
+        }
+        let destination = self.root()?.join(timestamp.to_string()).join(name);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent).context("Unable to create trash directory")?;
+        }
+        fs::rename(path, &destination).context("Unable to move virtual env to trash")?;
+        info!("Moved {name} to trash");
+        Ok(())
+    }
+
+    // Entries sorted from the oldest to the most recent
+    pub fn list(&self) -> Vec<TrashEntry> {
+        let Ok(root) = self.root() else {
+            return vec![];
+        };
+        let Ok(dirs) = fs::read_dir(&root) else {
+            return vec![];
+        };
+        dirs.flatten()
+            .filter_map(|dir| {
+                let millis: u64 = dir.file_name().to_str()?.parse().ok()?;
+                let deleted_at = UNIX_EPOCH + Duration::from_millis(millis);
+                let dir = dir.path();
+                let mut names = get_venvs_by_glob("*/*".into(), &dir).unwrap_or_default();
+                names.extend(get_venvs_by_glob("*".into(), &dir).unwrap_or_default());
+                Some(
+                    names
+                        .into_iter()
+                        .map(|name| TrashEntry {
+                            path: dir.join(&name),
+                            name,
+                            deleted_at,
+                        })
+                        .collect_vec(),
+                )
+            })
+            .flatten()
+            .sorted_by_key(|entry| entry.deleted_at)
+            .collect()
+    }
+
+    // Moves the most recently deleted venv with the given name to the destination
+    pub fn restore(&self, name: &str, destination: &Path) -> Result<()> {
+        let Some(entry) = self.list().into_iter().rev().find(|e| e.name == name) else {
+            bail!("{name} is not in the trash");
+        };
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)
+                .context("Unable to create root directory for virtual env")?;
+        }
+        fs::rename(&entry.path, destination).context("Unable to restore virtual env")?;
+        self.remove_empty_dirs(&entry.path);
+        Ok(())
+    }
+
+    fn remove_empty_dirs(&self, path: &Path) {
+        let Ok(root) = self.root() else {
+            return;
+        };
+        let mut current = path.parent();
+        while let Some(dir) = current {
+            if dir == root || fs::remove_dir(dir).is_err() {
+                break;
+            }
+            current = dir.parent();
+        }
+    }
+
+    // Permanently deletes entries deleted earlier than `older_than` ago (all entries if None)
+    pub fn empty(&self, older_than: Option<Duration>) -> Result<usize> {
+        let now = SystemTime::now();
+        let mut removed = 0;
+        for entry in self.list() {
+            let age = now.duration_since(entry.deleted_at).unwrap_or_default();
+            if older_than.is_some_and(|limit| age < limit) {
+                continue;
+            }
+            fs::remove_dir_all(&entry.path)
+                .with_context(|| format!("Unable to delete {}", entry.path.display()))?;
+            self.remove_empty_dirs(&entry.path);
+            info!("Permanently deleted {}", entry.name);
+            // Snapshots are kept while the venv can be restored
+            if !Rsenv.list().contains(&entry.name) {
+                Snapshots {
+                    name: &entry.name,
+                    home: self.home.clone(),
+                }
+                .delete_all()?;
+            }
+            removed += 1;
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn venv(path: &Path, content: &str) {
+        fs::create_dir_all(path.join("bin")).unwrap();
+        fs::write(path.join("bin").join("activate"), content).unwrap();
+    }
+
+    #[test]
+    fn test_put_restore() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let trash = Trash {
+            home: Some(dir.path().to_path_buf()),
+        };
+        let path = dir.path().join("venvs").join("3.11.7").join("project");
+        venv(&path, "first");
+        trash.put("3.11.7/project", &path).unwrap();
+        assert!(!path.exists());
+        venv(&path, "second");
+        trash.put("3.11.7/project", &path).unwrap();
+
+        let entries = trash.list();
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|e| e.name == "3.11.7/project"));
+        assert!(entries[0].deleted_at < entries[1].deleted_at);
+
+        // The most recently deleted venv is restored first
+        trash.restore("3.11.7/project", &path).unwrap();
+        assert_eq!(
+            fs::read_to_string(path.join("bin").join("activate")).unwrap(),
+            "second"
+        );
+        assert_eq!(trash.list().len(), 1);
+        assert!(trash.restore("3.11.7/other", &path).is_err());
+    }
+
+    #[test]
+    fn test_empty_older_than() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let trash = Trash {
+            home: Some(dir.path().to_path_buf()),
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+        let day = 24 * 60 * 60 * 1000;
+        let root = dir.path().join("trash");
+        venv(
+            &root.join((now - 10 * day).to_string()).join("3.11.7/old"),
+            "",
+        );
+        venv(
+            &root.join((now - day).to_string()).join("3.11.7/recent"),
+            "",
+        );
+
+        let week = Duration::from_secs(7 * 24 * 60 * 60);
+        assert_eq!(trash.empty(Some(week)).unwrap(), 1);
+        let entries = trash.list();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "3.11.7/recent");
+        // Directories of the removed entry are removed as well
+        assert!(!root.join((now - 10 * day).to_string()).exists());
+
+        assert_eq!(trash.empty(None).unwrap(), 1);
+        assert!(trash.list().is_empty());
+    }
+}
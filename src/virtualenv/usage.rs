@@ -0,0 +1,143 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    os::unix::fs::MetadataExt,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+// Disk usage of a venv: site-packages and cache (__pycache__) are parts of the total
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DiskUsage {
+    pub total: u64,
+    pub site_packages: u64,
+    pub cache: u64,
+}
+
+impl DiskUsage {
+    fn add(&mut self, size: u64, site_packages: bool, cache: bool) {
+        self.total += size;
+        if site_packages {
+            self.site_packages += size;
+        }
+        if cache {
+            self.cache += size;
+        }
+    }
+}
+
+// Usage of the files with a single link and the files with several ones by (device, inode),
+// which may be shared with other venvs
+#[derive(Debug, Default)]
+struct Walk {
+    usage: DiskUsage,
+    linked: Vec<((u64, u64), u64, bool, bool)>,
+}
+
+fn walk(path: &Path, site_packages: bool, cache: bool, result: &mut Walk) {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return;
+    };
+    if metadata.is_dir() {
+        let name = path.file_name().and_then(|name| name.to_str());
+        let site_packages = site_packages || name == Some("site-packages");
+        let cache = cache || name == Some("__pycache__");
+        for entry in fs::read_dir(path).into_iter().flatten().flatten() {
+            walk(&entry.path(), site_packages, cache, result);
+        }
+    } else if metadata.nlink() > 1 {
+        result.linked.push((
+            (metadata.dev(), metadata.ino()),
+            metadata.len(),
+            site_packages,
+            cache,
+        ));
+    } else {
+        result.usage.add(metadata.len(), site_packages, cache);
+    }
+}
+
+// Walks the venvs in parallel. Bytes shared by hardlinks are counted once,
+// for the first venv (in the given order) containing them
+pub fn disk_usage(paths: &[&Path]) -> Vec<DiskUsage> {
+    let walks: Mutex<HashMap<usize, Walk>> = Mutex::new(HashMap::new());
+    let next = AtomicUsize::new(0);
+    let workers = thread::available_parallelism().map_or(1, |n| n.get());
+    thread::scope(|scope| {
+        for _ in 0..workers.min(paths.len()) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(path) = paths.get(index) else {
+                    break;
+                };
+                let mut result = Walk::default();
+                walk(path, false, false, &mut result);
+                walks
+                    .lock()
+                    .expect("disk usage worker panicked")
+                    .insert(index, result);
+            });
+        }
+    });
+    let mut walks = walks.into_inner().expect("disk usage worker panicked");
+    let mut seen = HashSet::new();
+    (0..paths.len())
+        .map(|index| {
+            let walk = walks.remove(&index).unwrap_or_default();
+            let mut usage = walk.usage;
+            for (inode, size, site_packages, cache) in walk.linked {
+                if seen.insert(inode) {
+                    usage.add(size, site_packages, cache);
+                }
+            }
+            usage
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disk_usage() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let first = dir.path().join("first");
+        let site_packages = first.join("lib").join("python3.11").join("site-packages");
+        fs::create_dir_all(site_packages.join("__pycache__")).unwrap();
+        fs::write(first.join("pyvenv.cfg"), [0u8; 10]).unwrap();
+        fs::write(site_packages.join("module.py"), [0u8; 100]).unwrap();
+        fs::write(
+            site_packages.join("__pycache__").join("module.pyc"),
+            [0u8; 50],
+        )
+        .unwrap();
+        let second = dir.path().join("second");
+        fs::create_dir_all(&second).unwrap();
+        fs::hard_link(site_packages.join("module.py"), second.join("module.py")).unwrap();
+        fs::hard_link(site_packages.join("module.py"), second.join("copy.py")).unwrap();
+        fs::write(second.join("pyvenv.cfg"), [0u8; 10]).unwrap();
+
+        let usage = disk_usage(&[&first, &second]);
+        assert_eq!(
+            usage[0],
+            DiskUsage {
+                total: 160,
+                site_packages: 150,
+                cache: 50
+            }
+        );
+        assert_eq!(
+            usage[1],
+            DiskUsage {
+                total: 10,
+                site_packages: 0,
+                cache: 0
+            }
+        );
+    }
+}
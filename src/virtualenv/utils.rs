@@ -1,10 +1,15 @@
 use std::{
     collections::HashSet,
     fs,
+    io::{self, IsTerminal, Write},
     path::{Path, PathBuf},
+    time::SystemTime,
 };
 
-use anyhow::{bail, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::configuration::SETTINGS;
+use simplelog::debug;
 
 pub fn is_virtualenv(path: &Path) -> Result<()> {
     if fs::metadata(path.join("bin").join("activate")).is_ok_and(|x| x.is_file()) {
@@ -14,10 +19,141 @@ pub fn is_virtualenv(path: &Path) -> Result<()> {
     }
 }
 
+// Writes a new file instead of modifying the existing one in place,
+// so hardlinked copies (clones, snapshots) keep their content
+pub fn replace_file(path: &Path, content: impl AsRef<[u8]>) -> io::Result<()> {
+    let tmp = path.with_extension("rsvenv-tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)
+}
+
+pub const LAST_USED_MARKER: &str = ".rsvenv-last-used";
+
+pub fn mark_used(path: &Path) {
+    if let Err(e) = replace_file(&path.join(LAST_USED_MARKER), "") {
+        debug!("Unable to mark {} as used: {e:?}", path.display());
+    }
+}
+
+// Time of the last activation, or the modification time if the venv has never been activated
+pub fn last_used(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path.join(LAST_USED_MARKER))
+        .or_else(|_| fs::metadata(path))
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+// Whether the venv is activated in the current shell
+pub fn is_active(path: &Path) -> bool {
+    match std::env::var("VIRTUAL_ENV") {
+        Ok(value) => fs::canonicalize(value).is_ok_and(|value| value == path),
+        Err(_) => false,
+    }
+}
+
 pub fn get_current_dir() -> Result<PathBuf> {
     std::env::current_dir().context("Unable to get current dir")
 }
 
+pub fn get_rsvenv_home() -> Result<PathBuf> {
+    let expanded = shellexpand::full(
+        &SETTINGS
+            .read()
+            .map_err(|e| anyhow!("Unable to read path from SETTINGS: {e:?}"))?
+            .path,
+    )
+    .context("unable to expand SETTINGS.path to the actual path")?
+    .to_string();
+    Ok(Path::new(&expanded).to_path_buf())
+}
+
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| dir_size(&entry.path())).sum())
+        .unwrap_or_default()
+}
+
+pub fn format_size(size: u64) -> String {
+    let mut value = size as f64;
+    for unit in ["B", "KiB", "MiB", "GiB"] {
+        if value < 1024.0 {
+            return format!("{value:.1} {unit}");
+        }
+        value /= 1024.0;
+    }
+    format!("{value:.1} TiB")
+}
+
+// Aligns columns of the rows, the first row being the header
+pub fn format_table(rows: &[Vec<String>]) -> String {
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or_default();
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| {
+            rows.iter()
+                .filter_map(|row| row.get(column))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    rows.iter()
+        .map(|row| {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            format!("{}\n", line.trim_end())
+        })
+        .collect()
+}
+
+// Copies the directory tree keeping symlinks. Files are hardlinked when possible
+pub fn copy_tree(src: &Path, dst: &Path, hardlink: bool) -> Result<()> {
+    fs::create_dir_all(dst).with_context(|| format!("Unable to create {}", dst.display()))?;
+    for entry in fs::read_dir(src).with_context(|| format!("Unable to read {}", src.display()))? {
+        let entry = entry.context("Unable to read directory entry")?;
+        let (from, to) = (entry.path(), dst.join(entry.file_name()));
+        let file_type = entry.file_type().context("Unable to get file type")?;
+        if file_type.is_symlink() {
+            let target = fs::read_link(&from).context("Unable to read symlink")?;
+            std::os::unix::fs::symlink(target, &to)
+                .with_context(|| format!("Unable to create symlink {}", to.display()))?;
+        } else if file_type.is_dir() {
+            copy_tree(&from, &to, hardlink)?;
+            fs::set_permissions(&to, entry.metadata()?.permissions())
+                .context("Unable to set permissions")?;
+        } else if !hardlink || fs::hard_link(&from, &to).is_err() {
+            fs::copy(&from, &to).with_context(|| format!("Unable to copy {}", from.display()))?;
+        }
+    }
+    Ok(())
+}
+
+pub fn confirm(question: &str) -> Result<bool> {
+    if !io::stdin().is_terminal() {
+        bail!("Unable to ask for confirmation: STDIN is not a terminal, use --yes to skip it");
+    }
+    // STDOUT can be evaluated by the shell, so the question goes to STDERR
+    let mut stderr = io::stderr();
+    stderr
+        .write_all(format!("{question} [y/N] ").as_bytes())
+        .context("Unable to write to STDERR")?;
+    stderr.flush().context("Unable to write to STDERR")?;
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .context("Unable to read answer from STDIN")?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
 pub fn get_venvs_by_glob(glob: String, dir: &PathBuf) -> Result<HashSet<String>> {
     let mut result = HashSet::new();
     for path in glob::glob(format!("{}/{glob}", &dir.as_path().display()).as_str())
@@ -43,7 +179,6 @@ mod tests {
     use std::fs::create_dir_all;
 
     use super::*;
-    use tempfile;
 
     #[test]
     fn test_is_virtualenv_ok() {
@@ -51,17 +186,71 @@ mod tests {
         let bin_dir = dir.as_ref().join("bin");
         fs::create_dir(&bin_dir).unwrap();
         fs::File::create(bin_dir.join("activate")).unwrap();
-        let result = is_virtualenv(&dir.as_ref().to_path_buf());
+        let result = is_virtualenv(dir.as_ref());
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_is_virtualenv_error() {
         let dir = tempfile::TempDir::new().unwrap();
-        let result = is_virtualenv(&dir.as_ref().to_path_buf());
+        let result = is_virtualenv(dir.as_ref());
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(100), "100.0 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+
+    #[test]
+    fn test_format_table() {
+        let rows = vec![
+            vec!["NAME".to_string(), "SIZE".to_string()],
+            vec!["3.11.7/project".to_string(), "1.0 KiB".to_string()],
+        ];
+        assert_eq!(
+            format_table(&rows),
+            "NAME            SIZE\n3.11.7/project  1.0 KiB\n"
+        );
+    }
+
+    #[test]
+    fn test_dir_size() {
+        let dir = tempfile::TempDir::new().unwrap();
+        create_dir_all(dir.as_ref().join("a").join("b")).unwrap();
+        fs::write(dir.as_ref().join("a").join("f1"), [0u8; 10]).unwrap();
+        fs::write(dir.as_ref().join("a").join("b").join("f2"), [0u8; 5]).unwrap();
+        assert_eq!(dir_size(dir.as_ref()), 15);
+    }
+
+    #[test]
+    fn test_copy_tree() {
+        use std::os::unix::fs::MetadataExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let src = dir.as_ref().join("src");
+        create_dir_all(src.join("bin")).unwrap();
+        fs::write(src.join("bin").join("script"), "content").unwrap();
+        std::os::unix::fs::symlink("script", src.join("bin").join("link")).unwrap();
+        let dst = dir.as_ref().join("dst");
+        copy_tree(&src, &dst, true).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dst.join("bin").join("script")).unwrap(),
+            "content"
+        );
+        assert_eq!(
+            fs::read_link(dst.join("bin").join("link")).unwrap(),
+            PathBuf::from("script")
+        );
+        assert_eq!(
+            fs::metadata(dst.join("bin").join("script")).unwrap().ino(),
+            fs::metadata(src.join("bin").join("script")).unwrap().ino()
+        );
+    }
+
     #[test]
     fn test_get_venvs_by_glob() {
         let dir = tempfile::TempDir::new().unwrap();
@@ -85,7 +274,7 @@ mod tests {
                 .join("activate"),
         )
         .unwrap();
-        let result = get_venvs_by_glob("*/*".into(), &dir.into_path()).unwrap();
+        let result = get_venvs_by_glob("*/*".into(), &dir.keep()).unwrap();
         // v2 is not a valid vitual environment
         let expected = HashSet::from([String::from("version/v1"), String::from("version/v3")]);
         assert_eq!(result, expected)